@@ -0,0 +1 @@
+ALTER TABLE moderation_log DROP COLUMN expires_at;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE moderation_log ADD COLUMN expires_at TIMESTAMP;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE stored_messages;
\ No newline at end of file
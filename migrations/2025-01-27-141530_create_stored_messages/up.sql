@@ -0,0 +1,12 @@
+CREATE TABLE stored_messages (
+    id BIGINT PRIMARY KEY,
+    guild BIGINT NOT NULL,
+    channel BIGINT NOT NULL,
+    author BIGINT NOT NULL,
+    content TEXT NOT NULL,
+    attachments JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    edited_at TIMESTAMP
+);
+
+CREATE INDEX stored_messages_guild_created_at_index ON stored_messages(guild, created_at);
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE message_log_ignores;
+DROP TYPE message_log_ignore_kind;
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TYPE message_log_ignore_kind AS ENUM ('channel', 'category', 'role', 'user', 'bots', 'webhooks');
+
+CREATE TABLE message_log_ignores (
+    guild BIGINT NOT NULL,
+    kind message_log_ignore_kind NOT NULL,
+    target BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (guild, kind, target)
+);
\ No newline at end of file
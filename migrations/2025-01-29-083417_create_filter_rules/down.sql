@@ -0,0 +1,3 @@
+DROP TABLE filter_rules;
+DROP TYPE filter_action;
+DROP TYPE filter_rule_kind;
\ No newline at end of file
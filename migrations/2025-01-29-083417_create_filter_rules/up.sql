@@ -0,0 +1,16 @@
+CREATE TYPE filter_rule_kind AS ENUM ('word', 'glob', 'regex');
+CREATE TYPE filter_action AS ENUM ('delete', 'warn', 'flood');
+
+CREATE TABLE filter_rules (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    guild BIGINT NOT NULL,
+    kind filter_rule_kind NOT NULL,
+    pattern TEXT NOT NULL,
+    action filter_action NOT NULL,
+    duration TEXT,
+    exempt_channels BIGINT[] NOT NULL DEFAULT '{}',
+    exempt_roles BIGINT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX filter_rules_guild ON filter_rules (guild);
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE raid_changes;
+DROP TYPE raid_change_kind;
\ No newline at end of file
@@ -0,0 +1,12 @@
+CREATE TYPE raid_change_kind AS ENUM ('slowmode', 'quarantine');
+
+CREATE TABLE raid_changes (
+    id uuid PRIMARY KEY DEFAULT gen_random_uuid(),
+    guild BIGINT NOT NULL,
+    kind raid_change_kind NOT NULL,
+    target BIGINT NOT NULL,
+    previous_value INT,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX raid_changes_guild ON raid_changes (guild);
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE lockdown_overwrites;
\ No newline at end of file
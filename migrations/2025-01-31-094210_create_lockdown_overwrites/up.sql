@@ -0,0 +1,12 @@
+CREATE TABLE lockdown_overwrites (
+    guild BIGINT NOT NULL,
+    channel BIGINT NOT NULL,
+    role BIGINT NOT NULL,
+    allow BIGINT,
+    deny BIGINT,
+    reason TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (channel, role)
+);
+
+CREATE INDEX lockdown_overwrites_guild ON lockdown_overwrites (guild);
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE phishing_domains;
\ No newline at end of file
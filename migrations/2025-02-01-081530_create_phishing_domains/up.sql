@@ -0,0 +1,6 @@
+CREATE TABLE phishing_domains (
+    guild BIGINT NOT NULL,
+    domain TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (guild, domain)
+);
\ No newline at end of file
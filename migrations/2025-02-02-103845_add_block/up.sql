@@ -0,0 +1 @@
+ALTER TYPE moderation_action ADD VALUE IF NOT EXISTS 'block' BEFORE 'warning';
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE voice_preferences;
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE voice_preferences (
+    guild BIGINT NOT NULL,
+    member BIGINT NOT NULL,
+    name TEXT,
+    user_limit INT,
+    bitrate INT,
+    region TEXT,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (guild, member)
+);
\ No newline at end of file
@@ -0,0 +1,5 @@
+ALTER TABLE voice_channels DROP COLUMN creator_channel, DROP COLUMN number;
+
+DROP TABLE voice_creators;
+
+DROP TYPE voice_creator_kind;
\ No newline at end of file
@@ -0,0 +1,21 @@
+CREATE TYPE voice_creator_kind AS ENUM ('voice', 'stage');
+
+CREATE TABLE voice_creators (
+    channel BIGINT PRIMARY KEY,
+    guild BIGINT NOT NULL,
+    name_pattern TEXT NOT NULL DEFAULT '{nick}',
+    category BIGINT,
+    user_limit INT,
+    bitrate INT,
+    kind voice_creator_kind NOT NULL DEFAULT 'voice',
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX voice_creators_guild ON voice_creators (guild);
+
+INSERT INTO voice_creators (channel, guild)
+SELECT value::BIGINT, guild FROM guild_settings WHERE key = 'creator_voice_channel' AND value IS NOT NULL;
+
+DELETE FROM guild_settings WHERE key = 'creator_voice_channel';
+
+ALTER TABLE voice_channels ADD COLUMN creator_channel BIGINT, ADD COLUMN number INT;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE voice_channels DROP COLUMN owner_left_at;
\ No newline at end of file
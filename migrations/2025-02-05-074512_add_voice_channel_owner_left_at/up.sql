@@ -0,0 +1 @@
+ALTER TABLE voice_channels ADD COLUMN owner_left_at TIMESTAMP;
\ No newline at end of file
@@ -0,0 +1,5 @@
+ALTER TABLE voice_preferences
+    DROP COLUMN locked,
+    DROP COLUMN hidden,
+    DROP COLUMN permitted,
+    DROP COLUMN rejected;
\ No newline at end of file
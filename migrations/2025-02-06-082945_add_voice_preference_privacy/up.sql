@@ -0,0 +1,5 @@
+ALTER TABLE voice_preferences
+    ADD COLUMN locked BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN hidden BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN permitted BIGINT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN rejected BIGINT[] NOT NULL DEFAULT '{}';
\ No newline at end of file
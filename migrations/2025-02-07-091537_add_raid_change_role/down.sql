@@ -0,0 +1 @@
+ALTER TABLE raid_changes DROP COLUMN role;
\ No newline at end of file
@@ -0,0 +1 @@
+ALTER TABLE raid_changes ADD COLUMN role BIGINT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE quarantined_members;
\ No newline at end of file
@@ -0,0 +1,7 @@
+CREATE TABLE quarantined_members (
+    guild BIGINT NOT NULL,
+    member BIGINT NOT NULL,
+    role BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (guild, member)
+);
\ No newline at end of file
@@ -4,8 +4,11 @@ use crate::{data::Data, Error};
 
 mod beep;
 mod case;
+mod lockdown;
 mod manage;
 mod moderation;
+mod quarantine;
+mod raid;
 mod role;
 mod temp_voice;
 
@@ -13,6 +16,8 @@ pub fn build_commands() -> Vec<Command<Data, Error>> {
     vec![
         beep::beep(),
         case::case(),
+        lockdown::lockdown(),
+        lockdown::unlock(),
         manage::sman(),
         moderation::slowmode(),
         moderation::inspect(),
@@ -24,6 +29,9 @@ pub fn build_commands() -> Vec<Command<Data, Error>> {
         moderation::unflood(),
         moderation::softban(),
         moderation::unsoftban(),
+        moderation::purge(),
+        quarantine::quarantine(),
+        raid::raid(),
         role::role(),
         role::temp_role(),
         temp_voice::temp_voice(),
@@ -0,0 +1,196 @@
+use fang::AsyncQueueable;
+use serenity::all::{ChannelType, GuildChannel, RoleId};
+
+use crate::{
+    features::lockdown::{get_locked_channels, lock_channel, unlock_channel, UnlockChannel},
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+/// The channel and its children if it's a category, or every channel of the server.
+async fn resolve_channels(
+    cx: &Context<'_>,
+    channel: Option<GuildChannel>,
+    server: bool,
+) -> Result<Vec<GuildChannel>, Error> {
+    let channels = cx.guild_id().unwrap().channels(cx).await?;
+    if server {
+        return Ok(channels.into_values().collect());
+    }
+    let Some(channel) = channel.or(cx.guild_channel().await) else {
+        return Ok(vec![]);
+    };
+    if channel.kind != ChannelType::Category {
+        return Ok(vec![channel]);
+    }
+    Ok(channels
+        .into_values()
+        .filter(|x| x.id == channel.id || x.parent_id == Some(channel.id))
+        .collect())
+}
+
+/// Parse role mentions or IDs separated by spaces, defaults to @everyone.
+fn parse_roles(cx: &Context<'_>, roles: Option<String>) -> Result<Vec<RoleId>, String> {
+    let Some(roles) = roles else {
+        return Ok(vec![RoleId::new(cx.guild_id().unwrap().get())]);
+    };
+    roles
+        .split_whitespace()
+        .map(|x| {
+            serenity::utils::parse_role_mention(x)
+                .or(x.parse().ok().filter(|x| *x > 0).map(RoleId::new))
+                .ok_or(format!("`{}` isn't a role.", x))
+        })
+        .collect()
+}
+
+/// Stop members from sending messages in a channel, a category or the whole server.
+///
+/// Example Usage:
+/// `/lockdown` - Locks the current channel for everyone.
+/// `/lockdown server:True duration:1h` - Locks every channel for an hour.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    category = "Moderation",
+    required_bot_permissions = "MANAGE_CHANNELS | MANAGE_ROLES",
+    default_member_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn lockdown(
+    cx: Context<'_>,
+    #[description = "Channel or category to lock, defaults to current channel"]
+    #[channel_types("Text", "News", "Forum", "Voice", "Stage", "Category")]
+    channel: Option<GuildChannel>,
+    #[description = "Lock every channel of the server"] server: Option<bool>,
+    #[description = "Roles to lock, defaults to @everyone"] roles: Option<String>,
+    #[description = "Reason of the lockdown"] reason: Option<String>,
+    #[description = "How long until the lockdown expires (e.g. 1h), ignore to unlock manually"]
+    duration: Option<String>,
+) -> Result<(), Error> {
+    let roles = match parse_roles(&cx, roles) {
+        Ok(x) => x,
+        Err(err) => {
+            cx.say(err).await?;
+            return Ok(());
+        }
+    };
+    let duration = match duration {
+        Some(mut duration) => match parse_duration_to_seconds(&duration) {
+            Ok(0) => {
+                cx.say("Invalid duration").await?;
+                return Ok(());
+            }
+            Ok(x) => {
+                if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+                    duration.push('s');
+                }
+                Some((duration, x))
+            }
+            Err(err) => {
+                cx.say(err).await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+    cx.defer_ephemeral().await?;
+    let guild_id = cx.guild_id().unwrap();
+    let actor = cx.author();
+    let audit_reason = format!(
+        "Locked by @{} ({}){}",
+        actor.name,
+        actor.id,
+        reason.map(|x| format!(": {}", x)).unwrap_or_default()
+    );
+    let pool = &cx.data().database;
+    let mut locked = 0;
+    for channel in resolve_channels(&cx, channel, server.unwrap_or_default()).await? {
+        match lock_channel(&cx, pool, &channel, &roles, &audit_reason).await {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(err) => {
+                log::warn!("Unable to lock channel {}: {}", channel.id, err);
+                continue;
+            }
+        }
+        locked += 1;
+        if let Some((_, duration)) = &duration {
+            cx.data()
+                .queue
+                .schedule_task(&UnlockChannel::new(guild_id, channel.id, *duration))
+                .await?;
+        }
+    }
+    cx.say(if locked == 0 {
+        "No channels have been locked, they may be locked already.".to_string()
+    } else {
+        format!(
+            "Locked **{}** channels{}.",
+            locked,
+            duration
+                .map(|x| format!(" for **{}**", x.0))
+                .unwrap_or_default()
+        )
+    })
+    .await?;
+    Ok(())
+}
+
+/// Restore the permissions of locked channels.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    category = "Moderation",
+    required_bot_permissions = "MANAGE_CHANNELS | MANAGE_ROLES",
+    default_member_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn unlock(
+    cx: Context<'_>,
+    #[description = "Channel or category to unlock, defaults to current channel"]
+    #[channel_types("Text", "News", "Forum", "Voice", "Stage", "Category")]
+    channel: Option<GuildChannel>,
+    #[description = "Unlock every locked channel of the server"] server: Option<bool>,
+) -> Result<(), Error> {
+    cx.defer_ephemeral().await?;
+    let guild_id = cx.guild_id().unwrap();
+    let pool = &cx.data().database;
+    let channels = if server.unwrap_or_default() {
+        get_locked_channels(pool, guild_id)?
+    } else {
+        resolve_channels(&cx, channel, false)
+            .await?
+            .into_iter()
+            .map(|x| x.id)
+            .collect()
+    };
+    let actor = cx.author();
+    let reason = format!("Unlocked by @{} ({})", actor.name, actor.id);
+    let mut unlocked = 0;
+    for channel in channels {
+        match unlock_channel(&cx, pool, channel, &reason).await {
+            Ok(true) => unlocked += 1,
+            Ok(false) => continue,
+            Err(err) => {
+                log::warn!("Unable to unlock channel {}: {}", channel, err);
+                continue;
+            }
+        }
+        if let Err(err) = cx
+            .data()
+            .queue
+            .remove_task_by_metadata(&UnlockChannel::new(guild_id, channel, 0))
+            .await
+        {
+            log::warn!("Unable to remove unlock task: {}", err);
+        }
+    }
+    cx.say(if unlocked == 0 {
+        "No locked channels have been found.".to_string()
+    } else {
+        format!("Unlocked **{}** channels.", unlocked)
+    })
+    .await?;
+    Ok(())
+}
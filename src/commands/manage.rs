@@ -2,12 +2,30 @@ use crate::{models::guild_settings::GuildSettings, ConnectionPool, Context, Erro
 use serenity::all::{GuildId, RoleId};
 
 mod allowed_roles;
+mod anti_spam;
 mod channels;
+mod filter;
+mod ghost_ping;
+mod invite_filter;
+mod mention_spam;
+mod message_store;
+mod phishing;
+mod quarantine;
+mod raid;
 mod softban;
 mod tempvoice;
 
 use allowed_roles::allowed_roles as sman_allowed_roles;
+use anti_spam::anti_spam as sman_anti_spam;
 use channels::channels as sman_channels;
+use filter::filter as sman_filter;
+use ghost_ping::ghost_ping as sman_ghost_ping;
+use invite_filter::invite_filter as sman_invite_filter;
+use mention_spam::mention_spam as sman_mention_spam;
+use message_store::message_store as sman_message_store;
+use phishing::phishing as sman_phishing;
+use quarantine::quarantine as sman_quarantine;
+use raid::raid as sman_raid;
 use softban::softban as sman_softban;
 use tempvoice::tempvoice as sman_tempvoice;
 
@@ -19,6 +37,15 @@ use tempvoice::tempvoice as sman_tempvoice;
         "sman_channels",
         "sman_allowed_roles",
         "sman_softban",
+        "sman_message_store",
+        "sman_ghost_ping",
+        "sman_anti_spam",
+        "sman_filter",
+        "sman_invite_filter",
+        "sman_mention_spam",
+        "sman_raid",
+        "sman_phishing",
+        "sman_quarantine",
         "set_flooder_role"
     ),
     default_member_permissions = "ADMINISTRATOR"
@@ -0,0 +1,169 @@
+use serenity::all::RoleId;
+
+use crate::{
+    features::anti_spam::{
+        get_exempt_roles, DELETE_SETTING, DUPLICATES_SETTING, DURATION_SETTING,
+        EXEMPT_ROLES_SETTING, INTERVAL_SETTING, MESSAGES_SETTING,
+    },
+    models::guild_settings::GuildSettings,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("configure", "disable", "add_exempt_role", "remove_exempt_role")
+)]
+pub async fn anti_spam(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Configure the anti-spam, spammers will be made Flooder automatically.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn configure(
+    cx: Context<'_>,
+    #[description = "How far back messages are counted in seconds"]
+    #[min = 1]
+    #[max = 600]
+    interval: u32,
+    #[description = "How many messages within the interval are spam, ignore to not limit the rate"]
+    #[min = 2]
+    messages: Option<u32>,
+    #[description = "How many identical messages or attachments within the interval are spam"]
+    #[min = 2]
+    duplicates: Option<u32>,
+    #[description = "How long spammers will be Flooder (e.g. 10m), defaults to 10m"]
+    duration: Option<String>,
+    #[description = "Whether the spam messages will be deleted"] delete: Option<bool>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    if messages.is_none() && duplicates.is_none() {
+        cx.say("Please set a message or duplicate limit.").await?;
+        return Ok(());
+    }
+    let mut duration = duration.unwrap_or("10m".to_string());
+    match parse_duration_to_seconds(&duration) {
+        Ok(0) => {
+            cx.say("Invalid duration").await?;
+            return Ok(());
+        }
+        Ok(_) => {}
+        Err(err) => {
+            cx.say(err).await?;
+            return Ok(());
+        }
+    }
+    if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+        duration.push('s');
+    }
+    GuildSettings::set(pool, guild_id, INTERVAL_SETTING, Some(interval.to_string()))?;
+    GuildSettings::set(
+        pool,
+        guild_id,
+        MESSAGES_SETTING,
+        messages.map(|x| x.to_string()),
+    )?;
+    GuildSettings::set(
+        pool,
+        guild_id,
+        DUPLICATES_SETTING,
+        duplicates.map(|x| x.to_string()),
+    )?;
+    GuildSettings::set(pool, guild_id, DURATION_SETTING, Some(&duration))?;
+    GuildSettings::set(
+        pool,
+        guild_id,
+        DELETE_SETTING,
+        delete.filter(|x| *x).map(|x| x.to_string()),
+    )?;
+    let mut limits = vec![];
+    if let Some(messages) = messages {
+        limits.push(format!("**{}** messages", messages));
+    }
+    if let Some(duplicates) = duplicates {
+        limits.push(format!(
+            "**{}** identical messages or attachments",
+            duplicates
+        ));
+    }
+    cx.say(format!(
+        "Users sending {} within **{}** seconds will be Flooder for **{}**{}.",
+        limits.join(" or "),
+        interval,
+        duration,
+        if delete == Some(true) {
+            " and their messages will be deleted"
+        } else {
+            ""
+        }
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Disable the anti-spam.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn disable(cx: Context<'_>) -> Result<(), Error> {
+    GuildSettings::set(
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        INTERVAL_SETTING,
+        None::<String>,
+    )?;
+    cx.say("The anti-spam has been disabled.").await?;
+    Ok(())
+}
+
+fn set_exempt_roles(cx: &Context<'_>, roles: &[RoleId]) -> Result<(), Error> {
+    GuildSettings::set_ids(
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        EXEMPT_ROLES_SETTING,
+        &roles.iter().map(|x| x.get()).collect::<Vec<u64>>(),
+    )?;
+    Ok(())
+}
+
+/// Exempt a role from the anti-spam.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn add_exempt_role(
+    cx: Context<'_>,
+    #[description = "The role that won't be checked"] role: RoleId,
+) -> Result<(), Error> {
+    let mut roles = get_exempt_roles(&cx.data().database, cx.guild_id().unwrap());
+    if roles.contains(&role) {
+        cx.say(format!("<@&{}> is already exempted.", role)).await?;
+        return Ok(());
+    }
+    roles.push(role);
+    set_exempt_roles(&cx, &roles)?;
+    cx.say(format!(
+        "<@&{}> has been exempted from the anti-spam.",
+        role
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Remove a role from the anti-spam exemptions.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn remove_exempt_role(
+    cx: Context<'_>,
+    #[description = "The role that will be checked again"] role: RoleId,
+) -> Result<(), Error> {
+    let mut roles = get_exempt_roles(&cx.data().database, cx.guild_id().unwrap());
+    if !roles.contains(&role) {
+        cx.say(format!("<@&{}> wasn't exempted.", role)).await?;
+        return Ok(());
+    }
+    roles.retain(|x| *x != role);
+    set_exempt_roles(&cx, &roles)?;
+    cx.say(format!(
+        "<@&{}> is no longer exempted from the anti-spam.",
+        role
+    ))
+    .await?;
+    Ok(())
+}
@@ -1,10 +1,25 @@
-use serenity::all::ChannelId;
+use diesel::{
+    dsl::{delete, insert_into},
+    ExpressionMethods, RunQueryDsl,
+};
+use serenity::all::{ChannelId, ChannelType, GuildChannel, RoleId, UserId};
 
-use crate::{commands::manage::set_server_id_impl, Context, Error};
+use crate::{
+    commands::manage::set_server_id_impl,
+    features::{member_log, message_change_log::get_ignores},
+    models::message_log_ignore::{CreateMessageLogIgnore, MessageLogIgnoreKind},
+    schema::message_log_ignores,
+    Context, Error,
+};
 
 #[poise::command(
     slash_command,
-    subcommands("set_moderation_log_channel", "set_message_change_log_channel")
+    subcommands(
+        "set_moderation_log_channel",
+        "set_message_change_log_channel",
+        "set_member_log_channel",
+        "message_log_ignore"
+    )
 )]
 pub async fn channels(_cx: Context<'_>) -> Result<(), Error> {
     Ok(())
@@ -55,3 +70,129 @@ pub async fn set_message_change_log_channel(
     .await?;
     Ok(())
 }
+
+/// Set the member log channel.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_member_log_channel(
+    cx: Context<'_>,
+    #[description = "The channel that will be the member log channel, ignore to disable"]
+    #[channel_types("Text")]
+    channel: Option<ChannelId>,
+) -> Result<(), Error> {
+    cx.say(
+        set_server_id_impl(
+            member_log::CHANNEL_SETTING,
+            "member log channel",
+            "#",
+            &cx.data().database,
+            cx.guild_id().unwrap(),
+            channel,
+        )
+        .await?,
+    )
+    .await?;
+    Ok(())
+}
+
+#[derive(Debug, poise::ChoiceParameter)]
+pub enum IgnoreAction {
+    #[name = "add"]
+    Add,
+    #[name = "remove"]
+    Remove,
+    #[name = "list"]
+    List,
+}
+
+/// Manage what the message change log ignores.
+///
+/// Messages in ignored channels/categories or from ignored roles/users won't be logged.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn message_log_ignore(
+    cx: Context<'_>,
+    #[description = "Add, remove or list the ignored targets"] action: IgnoreAction,
+    #[description = "Channel or category to ignore"]
+    #[channel_types("Text", "Voice", "News", "Stage", "Forum", "Category")]
+    channel: Option<GuildChannel>,
+    #[description = "Role to ignore"] role: Option<RoleId>,
+    #[description = "User to ignore"] user: Option<UserId>,
+    #[description = "Ignore messages from bots"] bots: Option<bool>,
+    #[description = "Ignore messages from webhooks"] webhooks: Option<bool>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    if let IgnoreAction::List = action {
+        let ignores = get_ignores(pool, guild_id);
+        cx.say(if ignores.is_empty() {
+            "The message change log doesn't ignore anything.".to_string()
+        } else {
+            format!(
+                "The message change log ignores:\n{}",
+                ignores
+                    .iter()
+                    .map(|x| format!("- {:?}: {}", x.kind, x.kind.mention(x.target)))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            )
+        })
+        .await?;
+        return Ok(());
+    }
+    let mut targets = vec![];
+    if let Some(channel) = channel {
+        targets.push(if channel.kind == ChannelType::Category {
+            (MessageLogIgnoreKind::Category, channel.id.get())
+        } else {
+            (MessageLogIgnoreKind::Channel, channel.id.get())
+        });
+    }
+    if let Some(role) = role {
+        targets.push((MessageLogIgnoreKind::Role, role.get()));
+    }
+    if let Some(user) = user {
+        targets.push((MessageLogIgnoreKind::User, user.get()));
+    }
+    if bots == Some(true) {
+        targets.push((MessageLogIgnoreKind::Bots, 0));
+    }
+    if webhooks == Some(true) {
+        targets.push((MessageLogIgnoreKind::Webhooks, 0));
+    }
+    if targets.is_empty() {
+        cx.say("Please specify something to ignore.").await?;
+        return Ok(());
+    }
+    let mut conn = pool.get()?;
+    let mut changed = vec![];
+    for (kind, target) in targets {
+        let count = match action {
+            IgnoreAction::Add => insert_into(message_log_ignores::table)
+                .values(CreateMessageLogIgnore::new(guild_id, kind, Some(target)))
+                .on_conflict_do_nothing()
+                .execute(&mut conn)?,
+            _ => delete(message_log_ignores::table)
+                .filter(
+                    message_log_ignores::guild
+                        .eq(TryInto::<i64>::try_into(guild_id.get()).unwrap()),
+                )
+                .filter(message_log_ignores::kind.eq(kind))
+                .filter(message_log_ignores::target.eq(TryInto::<i64>::try_into(target).unwrap()))
+                .execute(&mut conn)?,
+        };
+        if count > 0 {
+            changed.push(kind.mention(target.try_into().unwrap()));
+        }
+    }
+    cx.say(match (&action, changed.is_empty()) {
+        (_, true) => "Nothing has been changed.".to_string(),
+        (IgnoreAction::Add, false) => {
+            format!("The message change log will ignore {}.", changed.join(", "))
+        }
+        (_, false) => format!(
+            "The message change log will no longer ignore {}.",
+            changed.join(", ")
+        ),
+    })
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,219 @@
+use std::str::FromStr;
+
+use diesel::{
+    dsl::{delete, insert_into, update},
+    ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl, SelectableHelper,
+};
+use serenity::all::{ChannelId, RoleId};
+use uuid::Uuid;
+
+use crate::{
+    features::filter::{compile_pattern, get_rules, invalidate_rules, normalize},
+    models::filter_rule::{CreateFilterRule, FilterAction, FilterRule, FilterRuleKind},
+    schema::filter_rules,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+/// Rule lists longer than this are cut off to fit in a message.
+const MAX_LIST_LENGTH: usize = 1900;
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("add", "remove", "list", "test", "exempt")
+)]
+pub async fn filter(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+fn parse_rule_id(id: &str) -> Result<Uuid, Error> {
+    Ok(Uuid::from_str(id).map_err(|_| "Rule ID is invalid.")?)
+}
+
+fn describe_rule(rule: &FilterRule) -> String {
+    let mut description = format!(
+        "`{}` {:?} `{}` → {}",
+        rule.id,
+        rule.kind,
+        rule.pattern.replace('`', "'"),
+        rule.action.description()
+    );
+    if let Some(duration) = &rule.duration {
+        description.push_str(&format!(" ({})", duration));
+    }
+    let exemptions = rule
+        .exempt_channels
+        .iter()
+        .map(|x| format!("<#{}>", x))
+        .chain(rule.exempt_roles.iter().map(|x| format!("<@&{}>", x)))
+        .collect::<Vec<String>>();
+    if !exemptions.is_empty() {
+        description.push_str(&format!(", except {}", exemptions.join(" ")));
+    }
+    description
+}
+
+/// Add a content filter rule.
+///
+/// Words and globs are matched after confusable characters are normalized.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn add(
+    cx: Context<'_>,
+    #[description = "How the pattern is matched"] kind: FilterRuleKind,
+    #[description = "The word, glob (e.g. nitro*) or regex"] pattern: String,
+    #[description = "What happens to matching messages"] action: FilterAction,
+    #[description = "Duration of the warning or Flooder (e.g. 1h), floods default to 10m"]
+    duration: Option<String>,
+) -> Result<(), Error> {
+    if let Err(err) = compile_pattern(kind, &pattern) {
+        cx.say(format!("The pattern is invalid: {}", err)).await?;
+        return Ok(());
+    }
+    let duration = match duration {
+        Some(mut duration) => {
+            match parse_duration_to_seconds(&duration) {
+                Ok(0) => {
+                    cx.say("Invalid duration").await?;
+                    return Ok(());
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    cx.say(err).await?;
+                    return Ok(());
+                }
+            }
+            if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+                duration.push('s');
+            }
+            Some(duration)
+        }
+        None => None,
+    };
+    let guild_id = cx.guild_id().unwrap();
+    let rule: FilterRule = insert_into(filter_rules::table)
+        .values(CreateFilterRule::new(
+            guild_id, kind, &pattern, action, duration,
+        ))
+        .returning(FilterRule::as_returning())
+        .get_result(&mut cx.data().database.get()?)?;
+    invalidate_rules(guild_id);
+    cx.say(format!("Added the rule {}", describe_rule(&rule)))
+        .await?;
+    Ok(())
+}
+
+/// Remove a content filter rule.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn remove(
+    cx: Context<'_>,
+    #[description = "ID of the rule"] id: String,
+) -> Result<(), Error> {
+    let guild_id = cx.guild_id().unwrap();
+    let count = delete(filter_rules::table)
+        .filter(filter_rules::id.eq(parse_rule_id(&id)?))
+        .filter(filter_rules::guild.eq(TryInto::<i64>::try_into(guild_id.get()).unwrap()))
+        .execute(&mut cx.data().database.get()?)?;
+    invalidate_rules(guild_id);
+    cx.say(if count > 0 {
+        "The rule has been removed."
+    } else {
+        "The rule doesn't exist."
+    })
+    .await?;
+    Ok(())
+}
+
+/// List the content filter rules.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn list(cx: Context<'_>) -> Result<(), Error> {
+    let rules = get_rules(&cx.data().database, cx.guild_id().unwrap())?;
+    if rules.is_empty() {
+        cx.say("There are no content filter rules.").await?;
+        return Ok(());
+    }
+    let mut content = String::new();
+    for (i, rule) in rules.iter().enumerate() {
+        let line = format!("- {}\n", describe_rule(&rule.rule));
+        if content.len() + line.len() > MAX_LIST_LENGTH {
+            content.push_str(&format!("_and {} more_", rules.len() - i));
+            break;
+        }
+        content.push_str(&line);
+    }
+    cx.say(content).await?;
+    Ok(())
+}
+
+/// Test which content filter rules match a message.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn test(
+    cx: Context<'_>,
+    #[description = "The message to test"] content: String,
+) -> Result<(), Error> {
+    let rules = get_rules(&cx.data().database, cx.guild_id().unwrap())?;
+    let normalized = normalize(&content);
+    let matched = rules
+        .iter()
+        .filter(|x| x.is_match(&content, &normalized))
+        .map(|x| format!("- {}", describe_rule(&x.rule)))
+        .collect::<Vec<String>>();
+    cx.say(format!(
+        "Normalized: `{}`\n{}",
+        normalized.replace('`', "'"),
+        if matched.is_empty() {
+            "No rules match.".to_string()
+        } else {
+            format!("Matching rules:\n{}", matched.join("\n"))
+        }
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Exempt a channel or role from a content filter rule.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn exempt(
+    cx: Context<'_>,
+    #[description = "ID of the rule"] id: String,
+    #[description = "Channel or category that won't be checked"]
+    #[channel_types("Text", "Voice", "News", "Stage", "Forum", "Category")]
+    channel: Option<ChannelId>,
+    #[description = "Role that won't be checked"] role: Option<RoleId>,
+    #[description = "Remove the exemptions instead"] remove: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = cx.guild_id().unwrap();
+    let mut conn = cx.data().database.get()?;
+    let Some(rule) = filter_rules::table
+        .filter(filter_rules::id.eq(parse_rule_id(&id)?))
+        .filter(filter_rules::guild.eq(TryInto::<i64>::try_into(guild_id.get()).unwrap()))
+        .select(FilterRule::as_select())
+        .get_result(&mut conn)
+        .optional()?
+    else {
+        cx.say("The rule doesn't exist.").await?;
+        return Ok(());
+    };
+    let remove = remove.unwrap_or(false);
+    let apply = |mut ids: Vec<i64>, id: Option<u64>| {
+        if let Some(id) = id.map(|x| TryInto::<i64>::try_into(x).unwrap()) {
+            ids.retain(|x| *x != id);
+            if !remove {
+                ids.push(id);
+            }
+        }
+        ids
+    };
+    let rule: FilterRule = update(filter_rules::table)
+        .filter(filter_rules::id.eq(rule.id))
+        .set((
+            filter_rules::exempt_channels.eq(apply(rule.exempt_channels, channel.map(|x| x.get()))),
+            filter_rules::exempt_roles.eq(apply(rule.exempt_roles, role.map(|x| x.get()))),
+        ))
+        .returning(FilterRule::as_returning())
+        .get_result(&mut conn)?;
+    invalidate_rules(guild_id);
+    cx.say(format!("Updated the rule {}", describe_rule(&rule)))
+        .await?;
+    Ok(())
+}
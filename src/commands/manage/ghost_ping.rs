@@ -0,0 +1,85 @@
+use serenity::all::ChannelId;
+
+use crate::{
+    features::ghost_ping::{NOTICE_CHANNEL_SETTING, WINDOW_SETTING},
+    models::guild_settings::GuildSettings,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("set_window", "set_notice_channel")
+)]
+pub async fn ghost_ping(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set how soon mentions have to disappear to be a ghost ping.
+///
+/// Deleted messages and messages with mentions edited out within the window are reported.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_window(
+    cx: Context<'_>,
+    #[description = "How soon mentions have to disappear (e.g. 5m), ignore to disable ghost ping detection"]
+    window: Option<String>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let Some(mut window) = window else {
+        GuildSettings::set(pool, guild_id, WINDOW_SETTING, None::<String>)?;
+        cx.say("Ghost ping detection has been disabled.").await?;
+        return Ok(());
+    };
+    let window_secs = match parse_duration_to_seconds(&window) {
+        Ok(x) => x,
+        Err(err) => {
+            cx.say(err).await?;
+            return Ok(());
+        }
+    };
+    if window_secs == 0 {
+        cx.say("Invalid duration").await?;
+        return Ok(());
+    }
+    if window.chars().last().is_some_and(|c| c.is_numeric()) {
+        window.push('s');
+    }
+    GuildSettings::set(
+        pool,
+        guild_id,
+        WINDOW_SETTING,
+        Some(window_secs.to_string()),
+    )?;
+    cx.say(format!(
+        "Mentions that disappear within **{}** will be reported as ghost pings.",
+        window
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Set the channel ghost ping notices are posted to.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_notice_channel(
+    cx: Context<'_>,
+    #[description = "The channel for ghost ping notices, ignore to post in the channel of the ping"]
+    #[channel_types("Text")]
+    channel: Option<ChannelId>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    GuildSettings::set(
+        pool,
+        guild_id,
+        NOTICE_CHANNEL_SETTING,
+        channel.map(|x| x.to_string()),
+    )?;
+    cx.say(match channel {
+        Some(channel) => format!("Ghost ping notices will be posted to <#{}>.", channel),
+        None => "Ghost ping notices will be posted in the channel of the ping.".to_string(),
+    })
+    .await?;
+    Ok(())
+}
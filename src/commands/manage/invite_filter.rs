@@ -0,0 +1,140 @@
+use serenity::all::{GuildId, RoleId};
+
+use crate::{
+    features::invite_filter::{ALLOWED_GUILDS_SETTING, ENABLED_SETTING, PARTNER_ROLES_SETTING},
+    models::guild_settings::GuildSettings,
+    Context, Error,
+};
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("enable", "disable", "allow_server", "partner_role", "list")
+)]
+pub async fn invite_filter(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Enable the invite filter.
+///
+/// Invites to servers that aren't allowed are deleted and the sender is warned.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn enable(cx: Context<'_>) -> Result<(), Error> {
+    GuildSettings::set(
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        ENABLED_SETTING,
+        Some("true"),
+    )?;
+    cx.say("The invite filter has been enabled.").await?;
+    Ok(())
+}
+
+/// Disable the invite filter.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn disable(cx: Context<'_>) -> Result<(), Error> {
+    GuildSettings::set(
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        ENABLED_SETTING,
+        None::<String>,
+    )?;
+    cx.say("The invite filter has been disabled.").await?;
+    Ok(())
+}
+
+/// Add or remove an id from a list setting, returns whether the list has been changed.
+fn update_ids(cx: &Context<'_>, key: &str, id: u64, remove: bool) -> Result<bool, Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let mut ids = GuildSettings::get_ids(pool, guild_id, key);
+    if ids.contains(&id) != remove {
+        return Ok(false);
+    }
+    if remove {
+        ids.retain(|x| *x != id);
+    } else {
+        ids.push(id);
+    }
+    GuildSettings::set_ids(pool, guild_id, key, &ids)?;
+    Ok(true)
+}
+
+/// Allow or disallow invites to a server.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn allow_server(
+    cx: Context<'_>,
+    #[description = "ID of the server"] server: String,
+    #[description = "Disallow the server instead"] remove: Option<bool>,
+) -> Result<(), Error> {
+    let Ok(server) = server.parse::<GuildId>() else {
+        cx.say("Server ID is invalid.").await?;
+        return Ok(());
+    };
+    let remove = remove.unwrap_or(false);
+    let changed = update_ids(&cx, ALLOWED_GUILDS_SETTING, server.get(), remove)?;
+    cx.say(match (changed, remove) {
+        (false, false) => format!("Invites to `{}` are already allowed.", server),
+        (false, true) => format!("Invites to `{}` weren't allowed.", server),
+        (true, false) => format!("Invites to `{}` are now allowed.", server),
+        (true, true) => format!("Invites to `{}` are no longer allowed.", server),
+    })
+    .await?;
+    Ok(())
+}
+
+/// Add or remove a partner role, partners can post any invite.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn partner_role(
+    cx: Context<'_>,
+    #[description = "The partner role"] role: RoleId,
+    #[description = "Remove the partner role instead"] remove: Option<bool>,
+) -> Result<(), Error> {
+    let remove = remove.unwrap_or(false);
+    let changed = update_ids(&cx, PARTNER_ROLES_SETTING, role.get(), remove)?;
+    cx.say(match (changed, remove) {
+        (false, false) => format!("<@&{}> is already a partner role.", role),
+        (false, true) => format!("<@&{}> wasn't a partner role.", role),
+        (true, false) => format!("<@&{}> can now post any invite.", role),
+        (true, true) => format!("<@&{}> is no longer a partner role.", role),
+    })
+    .await?;
+    Ok(())
+}
+
+/// List the allowed servers and partner roles.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn list(cx: Context<'_>) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let servers = GuildSettings::get_ids(pool, guild_id, ALLOWED_GUILDS_SETTING);
+    let roles = GuildSettings::get_ids(pool, guild_id, PARTNER_ROLES_SETTING);
+    cx.say(format!(
+        "The invite filter is **{}**.\nAllowed servers: {}\nPartner roles: {}",
+        if GuildSettings::get(pool, guild_id, ENABLED_SETTING).is_some() {
+            "enabled"
+        } else {
+            "disabled"
+        },
+        if servers.is_empty() {
+            "_None_".to_string()
+        } else {
+            servers
+                .iter()
+                .map(|x| format!("`{}`", x))
+                .collect::<Vec<String>>()
+                .join(" ")
+        },
+        if roles.is_empty() {
+            "_None_".to_string()
+        } else {
+            roles
+                .iter()
+                .map(|x| format!("<@&{}>", x))
+                .collect::<Vec<String>>()
+                .join(" ")
+        }
+    ))
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use crate::{
+    features::{
+        mention_spam::{
+            MentionAction, ACTION_SETTING, DURATION_SETTING, MESSAGE_LIMIT_SETTING,
+            WINDOW_LIMIT_SETTING, WINDOW_SETTING,
+        },
+        moderation::MAX_TIMEOUT_SECS,
+    },
+    models::guild_settings::GuildSettings,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+#[poise::command(slash_command, guild_only, subcommands("configure", "disable"))]
+pub async fn mention_spam(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Configure the mass mention protection.
+///
+/// Messages over the limits are deleted and the sender is punished.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn configure(
+    cx: Context<'_>,
+    #[description = "What happens to users mentioning too much"] action: MentionAction,
+    #[description = "Max unique users and roles mentioned in a message"]
+    #[min = 1]
+    per_message: Option<u32>,
+    #[description = "Max unique users and roles mentioned within the window"]
+    #[min = 1]
+    per_window: Option<u32>,
+    #[description = "The window in seconds"]
+    #[min = 1]
+    #[max = 3600]
+    window: Option<u32>,
+    #[description = "Duration of the timeout or Flooder (e.g. 1h), defaults to 1h"]
+    duration: Option<String>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    if per_message.is_none() && per_window.is_none() {
+        cx.say("Please set a per message or per window limit.")
+            .await?;
+        return Ok(());
+    }
+    if per_window.is_some() && window.is_none() {
+        cx.say("Please set the window for the per window limit.")
+            .await?;
+        return Ok(());
+    }
+    let mut duration = duration.unwrap_or("1h".to_string());
+    match parse_duration_to_seconds(&duration) {
+        Ok(0) => {
+            cx.say("Invalid duration").await?;
+            return Ok(());
+        }
+        Ok(x) if action == MentionAction::Timeout && x > MAX_TIMEOUT_SECS => {
+            cx.say("Timeouts can't be longer than 28 days.").await?;
+            return Ok(());
+        }
+        Ok(_) => {}
+        Err(err) => {
+            cx.say(err).await?;
+            return Ok(());
+        }
+    }
+    if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+        duration.push('s');
+    }
+    GuildSettings::set(
+        pool,
+        guild_id,
+        MESSAGE_LIMIT_SETTING,
+        per_message.map(|x| x.to_string()),
+    )?;
+    GuildSettings::set(
+        pool,
+        guild_id,
+        WINDOW_LIMIT_SETTING,
+        per_window.map(|x| x.to_string()),
+    )?;
+    GuildSettings::set(
+        pool,
+        guild_id,
+        WINDOW_SETTING,
+        window.map(|x| x.to_string()),
+    )?;
+    GuildSettings::set(pool, guild_id, ACTION_SETTING, Some(action.as_str()))?;
+    GuildSettings::set(pool, guild_id, DURATION_SETTING, Some(&duration))?;
+    let mut limits = vec![];
+    if let Some(per_message) = per_message {
+        limits.push(format!("more than **{}** in a message", per_message));
+    }
+    if let (Some(per_window), Some(window)) = (per_window, window) {
+        limits.push(format!(
+            "more than **{}** within **{}** seconds",
+            per_window, window
+        ));
+    }
+    cx.say(format!(
+        "Users mentioning {} will get **{}**{}.",
+        limits.join(" or "),
+        action.as_str(),
+        if action == MentionAction::Softban {
+            String::new()
+        } else {
+            format!(" for **{}**", duration)
+        }
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Disable the mass mention protection.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn disable(cx: Context<'_>) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    GuildSettings::set(pool, guild_id, MESSAGE_LIMIT_SETTING, None::<String>)?;
+    GuildSettings::set(pool, guild_id, WINDOW_LIMIT_SETTING, None::<String>)?;
+    cx.say("The mass mention protection has been disabled.")
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use crate::{
+    features::{
+        attachment_archive::{self, MAX_ARCHIVE_SIZE_MB, MAX_SIZE_SETTING},
+        message_store::{self, RETENTION_SETTING},
+    },
+    models::guild_settings::GuildSettings,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("set_retention", "set_attachment_archive")
+)]
+pub async fn message_store(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Set how long messages are kept for message change logs.
+///
+/// Stored messages are used when a deleted or edited message is no longer cached.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_retention(
+    cx: Context<'_>,
+    #[description = "How long messages will be kept (e.g. 7d), ignore to disable the message store"]
+    duration: Option<String>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let Some(mut duration) = duration else {
+        GuildSettings::set(pool, guild_id, RETENTION_SETTING, None::<String>)?;
+        cx.say("The message store has been disabled.").await?;
+        return Ok(());
+    };
+    let duration_secs = match parse_duration_to_seconds(&duration) {
+        Ok(x) => x,
+        Err(err) => {
+            cx.say(err).await?;
+            return Ok(());
+        }
+    };
+    if duration_secs == 0 {
+        cx.say("Invalid duration").await?;
+        return Ok(());
+    }
+    if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+        duration.push('s');
+    }
+    GuildSettings::set(
+        pool,
+        guild_id,
+        RETENTION_SETTING,
+        Some(duration_secs.to_string()),
+    )?;
+    cx.say(format!("Messages will be kept for **{}**.", duration))
+        .await?;
+    Ok(())
+}
+
+/// Set the max size of attachments that will be archived for message change logs.
+///
+/// Archived attachments are kept as long as stored messages.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_attachment_archive(
+    cx: Context<'_>,
+    #[description = "Max size of an archived attachment in MB, ignore to disable archiving"]
+    #[min = 1]
+    #[max = 25]
+    max_size: Option<u32>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let Some(max_size) = max_size else {
+        GuildSettings::set(pool, guild_id, MAX_SIZE_SETTING, None::<String>)?;
+        cx.say("The attachment archive has been disabled.").await?;
+        return Ok(());
+    };
+    if !attachment_archive::is_available() {
+        cx.say("The attachment archive is not configured for this bot.")
+            .await?;
+        return Ok(());
+    }
+    if !message_store::is_enabled(pool, guild_id) {
+        cx.say("The message store needs to be enabled first.")
+            .await?;
+        return Ok(());
+    }
+    let max_size = max_size.min(MAX_ARCHIVE_SIZE_MB);
+    GuildSettings::set(
+        pool,
+        guild_id,
+        MAX_SIZE_SETTING,
+        Some((max_size * 1024 * 1024).to_string()),
+    )?;
+    cx.say(format!(
+        "Attachments up to **{} MB** will be archived.",
+        max_size
+    ))
+    .await?;
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use diesel::{delete, insert_into, ExpressionMethods, RunQueryDsl};
+
+use crate::{
+    features::{
+        moderation::MAX_TIMEOUT_SECS,
+        phishing::{
+            get_guild_domains, invalidate_guild_domains, normalize_domain, reload_domains,
+            PhishingAction, ACTION_SETTING, DURATION_SETTING, ENABLED_SETTING,
+        },
+    },
+    models::{guild_settings::GuildSettings, phishing_domain::CreatePhishingDomain},
+    schema::phishing_domains,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("enable", "disable", "add", "remove", "list", "reload")
+)]
+pub async fn phishing(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Delete messages with phishing links and punish the sender.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn enable(
+    cx: Context<'_>,
+    #[description = "What happens to users posting phishing links, defaults to softban"]
+    action: Option<PhishingAction>,
+    #[description = "Duration of the timeout or Flooder (e.g. 1d), defaults to 1d"]
+    duration: Option<String>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let action = action.unwrap_or(PhishingAction::Softban);
+    let mut duration = duration.unwrap_or("1d".to_string());
+    match parse_duration_to_seconds(&duration) {
+        Ok(0) => {
+            cx.say("Invalid duration").await?;
+            return Ok(());
+        }
+        Ok(x) if action == PhishingAction::Timeout && x > MAX_TIMEOUT_SECS => {
+            cx.say("Timeouts can't be longer than 28 days.").await?;
+            return Ok(());
+        }
+        Ok(_) => {}
+        Err(err) => {
+            cx.say(err).await?;
+            return Ok(());
+        }
+    }
+    if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+        duration.push('s');
+    }
+    GuildSettings::set(pool, guild_id, ENABLED_SETTING, Some("true"))?;
+    GuildSettings::set(pool, guild_id, ACTION_SETTING, Some(action.as_str()))?;
+    GuildSettings::set(pool, guild_id, DURATION_SETTING, Some(&duration))?;
+    cx.say(format!(
+        "Phishing links will be deleted{}.",
+        match action {
+            PhishingAction::Delete => String::new(),
+            PhishingAction::Softban => " and the sender will be softbanned".to_string(),
+            _ => format!(
+                " and the sender will get **{}** for **{}**",
+                action.as_str(),
+                duration
+            ),
+        }
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Stop checking messages for phishing links.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn disable(cx: Context<'_>) -> Result<(), Error> {
+    GuildSettings::set(
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        ENABLED_SETTING,
+        None::<String>,
+    )?;
+    cx.say("The phishing filter has been disabled.").await?;
+    Ok(())
+}
+
+/// Add a phishing domain for this server, subdomains are matched too.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn add(
+    cx: Context<'_>,
+    #[description = "The domain (e.g. example.com)"] domain: String,
+) -> Result<(), Error> {
+    let guild_id = cx.guild_id().unwrap();
+    let domain = normalize_domain(&domain);
+    if !domain.contains('.') || domain.contains(char::is_whitespace) {
+        cx.say("Invalid domain").await?;
+        return Ok(());
+    }
+    let inserted = insert_into(phishing_domains::table)
+        .values(CreatePhishingDomain::new(guild_id, &domain))
+        .on_conflict_do_nothing()
+        .execute(&mut cx.data().database.get()?)?;
+    invalidate_guild_domains(guild_id);
+    cx.say(if inserted == 0 {
+        format!("`{}` is already listed.", domain)
+    } else {
+        format!("`{}` has been added to the phishing domains.", domain)
+    })
+    .await?;
+    Ok(())
+}
+
+/// Remove a phishing domain of this server.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn remove(
+    cx: Context<'_>,
+    #[description = "The domain (e.g. example.com)"] domain: String,
+) -> Result<(), Error> {
+    let guild_id = cx.guild_id().unwrap();
+    let domain = normalize_domain(&domain);
+    let deleted = delete(phishing_domains::table)
+        .filter(phishing_domains::guild.eq(TryInto::<i64>::try_into(guild_id.get()).unwrap()))
+        .filter(phishing_domains::domain.eq(&domain))
+        .execute(&mut cx.data().database.get()?)?;
+    invalidate_guild_domains(guild_id);
+    cx.say(if deleted == 0 {
+        format!("`{}` isn't listed.", domain)
+    } else {
+        format!("`{}` has been removed from the phishing domains.", domain)
+    })
+    .await?;
+    Ok(())
+}
+
+/// List the phishing domains added to this server.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn list(cx: Context<'_>) -> Result<(), Error> {
+    let domains = get_guild_domains(&cx.data().database, cx.guild_id().unwrap())?;
+    if domains.is_empty() {
+        cx.say("No phishing domains have been added to this server.")
+            .await?;
+        return Ok(());
+    }
+    let mut domains: Vec<&String> = domains.iter().collect();
+    domains.sort();
+    let mut content = String::from("Phishing domains of this server:\n");
+    for domain in domains {
+        let line = format!("- `{}`\n", domain);
+        if content.len() + line.len() > 1900 {
+            content.push_str("...");
+            break;
+        }
+        content.push_str(&line);
+    }
+    cx.say(content).await?;
+    Ok(())
+}
+
+/// Reload the phishing domain list from disk.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn reload(cx: Context<'_>) -> Result<(), Error> {
+    let content = match reload_domains() {
+        Ok(count) => format!("Loaded **{}** phishing domains.", count),
+        Err(err) => {
+            log::error!("Unable to reload phishing domains: {err}");
+            "Unable to reload the phishing domains.".to_string()
+        }
+    };
+    cx.say(content).await?;
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use serenity::all::RoleId;
+
+use crate::{
+    features::quarantine::{ACCOUNT_AGE_SETTING, ROLE_SETTING},
+    models::guild_settings::GuildSettings,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+#[poise::command(slash_command, guild_only, subcommands("configure", "disable"))]
+pub async fn quarantine(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Quarantine members joining with new accounts until the account is old enough.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn configure(
+    cx: Context<'_>,
+    #[description = "The quarantine role"] role: RoleId,
+    #[description = "Accounts younger than this are quarantined (e.g. 7d)"] account_age: String,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let mut account_age = account_age;
+    match parse_duration_to_seconds(&account_age) {
+        Ok(0) => {
+            cx.say("Invalid duration").await?;
+            return Ok(());
+        }
+        Ok(_) => {}
+        Err(err) => {
+            cx.say(err).await?;
+            return Ok(());
+        }
+    }
+    if account_age.chars().last().is_some_and(|c| c.is_numeric()) {
+        account_age.push('s');
+    }
+    GuildSettings::set(pool, guild_id, ROLE_SETTING, Some(role.to_string()))?;
+    GuildSettings::set(pool, guild_id, ACCOUNT_AGE_SETTING, Some(&account_age))?;
+    cx.say(format!(
+        "Members with accounts younger than **{}** will get <@&{}> until the account is old enough.",
+        account_age, role
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Stop quarantining new accounts.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn disable(cx: Context<'_>) -> Result<(), Error> {
+    GuildSettings::set(
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        ACCOUNT_AGE_SETTING,
+        None::<String>,
+    )?;
+    cx.say("New accounts will no longer be quarantined.")
+        .await?;
+    Ok(())
+}
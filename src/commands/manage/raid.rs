@@ -0,0 +1,201 @@
+use serenity::all::{ChannelId, GuildChannel, RoleId};
+
+use crate::{
+    features::raid::{
+        ALERT_CHANNEL_SETTING, ALERT_ROLE_SETTING, DEFAULT_DURATION, DEFAULT_SLOWMODE,
+        DURATION_SETTING, JOIN_LIMIT_SETTING, JOIN_WINDOW_SETTING, QUARANTINE_ROLE_SETTING,
+        SLOWMODE_CHANNELS_SETTING, SLOWMODE_SETTING,
+    },
+    models::guild_settings::GuildSettings,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
+
+use super::set_server_id_impl;
+
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands(
+        "configure",
+        "disable",
+        "slowmode_channel",
+        "set_quarantine_role",
+        "set_alert_channel",
+        "set_alert_role"
+    )
+)]
+pub async fn raid(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Configure the join rate that starts raid mode.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn configure(
+    cx: Context<'_>,
+    #[description = "How many joins within the window start raid mode"]
+    #[min = 2]
+    joins: u32,
+    #[description = "The window in seconds"]
+    #[min = 1]
+    #[max = 3600]
+    window: u32,
+    #[description = "Slowmode applied to the raid channels in seconds, defaults to 30"]
+    #[min = 1]
+    #[max = 21600]
+    slowmode: Option<u16>,
+    #[description = "How long raid mode lasts (e.g. 30m), 0 to only end it manually, defaults to 30m"]
+    duration: Option<String>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let mut duration = duration.unwrap_or(DEFAULT_DURATION.to_string());
+    let duration_secs = match parse_duration_to_seconds(&duration) {
+        Ok(x) => x,
+        Err(err) => {
+            cx.say(err).await?;
+            return Ok(());
+        }
+    };
+    if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+        duration.push('s');
+    }
+    let slowmode = slowmode.unwrap_or(DEFAULT_SLOWMODE);
+    GuildSettings::set(pool, guild_id, JOIN_LIMIT_SETTING, Some(joins.to_string()))?;
+    GuildSettings::set(
+        pool,
+        guild_id,
+        JOIN_WINDOW_SETTING,
+        Some(window.to_string()),
+    )?;
+    GuildSettings::set(pool, guild_id, SLOWMODE_SETTING, Some(slowmode.to_string()))?;
+    GuildSettings::set(pool, guild_id, DURATION_SETTING, Some(&duration))?;
+    cx.say(format!(
+        "Raid mode will start when **{}** members join within **{}** seconds and {}.",
+        joins,
+        window,
+        if duration_secs > 0 {
+            format!("end after **{}**", duration)
+        } else {
+            "has to be ended manually".to_string()
+        }
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Stop starting raid mode automatically.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn disable(cx: Context<'_>) -> Result<(), Error> {
+    GuildSettings::set(
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        JOIN_LIMIT_SETTING,
+        None::<String>,
+    )?;
+    cx.say("Raid mode will no longer start automatically.")
+        .await?;
+    Ok(())
+}
+
+/// Add or remove a channel that gets slowmode during raid mode.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn slowmode_channel(
+    cx: Context<'_>,
+    #[description = "The channel"]
+    #[channel_types("Text")]
+    channel: GuildChannel,
+    #[description = "Remove the channel instead"] remove: Option<bool>,
+) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let mut channels = GuildSettings::get_ids(pool, guild_id, SLOWMODE_CHANNELS_SETTING);
+    let message = if remove.unwrap_or_default() {
+        if !channels.contains(&channel.id.get()) {
+            cx.say(format!("<#{}> isn't a raid channel.", channel.id))
+                .await?;
+            return Ok(());
+        }
+        channels.retain(|x| *x != channel.id.get());
+        format!(
+            "<#{}> will no longer get slowmode during raids.",
+            channel.id
+        )
+    } else {
+        if channels.contains(&channel.id.get()) {
+            cx.say(format!("<#{}> is already a raid channel.", channel.id))
+                .await?;
+            return Ok(());
+        }
+        channels.push(channel.id.get());
+        format!("<#{}> will get slowmode during raids.", channel.id)
+    };
+    GuildSettings::set_ids(pool, guild_id, SLOWMODE_CHANNELS_SETTING, &channels)?;
+    cx.say(message).await?;
+    Ok(())
+}
+
+/// Set the role given to members joining during raid mode.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_quarantine_role(
+    cx: Context<'_>,
+    #[description = "The quarantine role, ignore to not quarantine"] role: Option<RoleId>,
+) -> Result<(), Error> {
+    cx.say(
+        set_server_id_impl(
+            QUARANTINE_ROLE_SETTING,
+            "raid quarantine role",
+            "@&",
+            &cx.data().database,
+            cx.guild_id().unwrap(),
+            role,
+        )
+        .await?,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set the channel raid alerts are sent to, defaults to the moderation log channel.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_alert_channel(
+    cx: Context<'_>,
+    #[description = "The alert channel, ignore to use the moderation log channel"]
+    #[channel_types("Text")]
+    channel: Option<ChannelId>,
+) -> Result<(), Error> {
+    cx.say(
+        set_server_id_impl(
+            ALERT_CHANNEL_SETTING,
+            "raid alert channel",
+            "#",
+            &cx.data().database,
+            cx.guild_id().unwrap(),
+            channel,
+        )
+        .await?,
+    )
+    .await?;
+    Ok(())
+}
+
+/// Set the staff role pinged by raid alerts.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_alert_role(
+    cx: Context<'_>,
+    #[description = "The role to ping, ignore to not ping"] role: Option<RoleId>,
+) -> Result<(), Error> {
+    cx.say(
+        set_server_id_impl(
+            ALERT_ROLE_SETTING,
+            "raid alert role",
+            "@&",
+            &cx.data().database,
+            cx.guild_id().unwrap(),
+            role,
+        )
+        .await?,
+    )
+    .await?;
+    Ok(())
+}
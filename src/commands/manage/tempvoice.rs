@@ -1,31 +1,179 @@
+use diesel::{delete, insert_into, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
 use serenity::all::ChannelId;
 
-use crate::{commands::manage::set_server_id_impl, Context, Error};
+use crate::{
+    features::temp_voice::{CLAIM_DELAY_SETTING, DEFAULT_CLAIM_DELAY},
+    models::{
+        guild_settings::GuildSettings,
+        voice_creator::{CreateVoiceCreator, VoiceCreator, VoiceCreatorKind},
+    },
+    schema::voice_creators,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
 
-#[poise::command(slash_command, guild_only, subcommands("set_channel"))]
+#[poise::command(
+    slash_command,
+    guild_only,
+    subcommands("add", "remove", "list", "set_claim_delay")
+)]
 pub async fn tempvoice(_cx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// Set the creator channel for temporary voice.
+fn describe_creator(creator: &VoiceCreator) -> String {
+    let mut description = format!(
+        "<#{}> → `{}`",
+        creator.channel,
+        creator.name_pattern.replace('`', "'")
+    );
+    if creator.kind != VoiceCreatorKind::Voice {
+        description.push_str(&format!(" ({:?})", creator.kind));
+    }
+    if let Some(category) = creator.category {
+        description.push_str(&format!(" in <#{}>", category));
+    }
+    if let Some(limit) = creator.user_limit {
+        description.push_str(&format!(", {} users", limit));
+    }
+    if let Some(bitrate) = creator.bitrate {
+        description.push_str(&format!(", {}kbps", bitrate / 1000));
+    }
+    description
+}
+
+/// Add a creator channel for temporary voice, or update its template.
+///
+/// Name patterns support `{nick}`, `{username}` and `{n}`, e.g. `{nick}'s room` or `Squad #{n}`.
 #[poise::command(slash_command, guild_only, ephemeral)]
-pub async fn set_channel(
+pub async fn add(
     cx: Context<'_>,
-    #[description = "The channel that will be the creator channel, ignore this to disable."]
+    #[description = "The channel that will be the creator channel"]
     #[channel_types("Voice")]
-    channel: Option<ChannelId>,
+    channel: ChannelId,
+    #[description = "Name of the created channels, defaults to {nick}"] name: Option<String>,
+    #[description = "Category of the created channels, defaults to the creator's"]
+    #[channel_types("Category")]
+    category: Option<ChannelId>,
+    #[description = "Default max user count of the created channels"]
+    #[min = 1]
+    #[max = 99]
+    user_limit: Option<u32>,
+    #[description = "Default bitrate of the created channels in kbps"]
+    #[min = 8]
+    #[max = 384]
+    bitrate: Option<u32>,
+    #[description = "Kind of the created channels, defaults to voice"] kind: Option<
+        VoiceCreatorKind,
+    >,
 ) -> Result<(), Error> {
-    cx.say(
-        set_server_id_impl(
-            "creator_voice_channel",
-            "creator voice channel",
-            "#",
-            &cx.data().database,
-            cx.guild_id().unwrap(),
-            channel,
+    let name = name.unwrap_or("{nick}".to_string());
+    if name.trim().is_empty() || name.chars().count() > 100 {
+        cx.say("The name has to be between 1 and 100 characters.")
+            .await?;
+        return Ok(());
+    }
+    let creator = CreateVoiceCreator::new(
+        channel,
+        cx.guild_id().unwrap(),
+        name.trim(),
+        category,
+        user_limit,
+        bitrate.map(|x| x * 1000),
+        kind.unwrap_or(VoiceCreatorKind::Voice),
+    );
+    let creator: VoiceCreator = insert_into(voice_creators::table)
+        .values(&creator)
+        .on_conflict(voice_creators::channel)
+        .do_update()
+        .set(&creator)
+        .returning(VoiceCreator::as_returning())
+        .get_result(&mut cx.data().database.get()?)?;
+    cx.say(format!(
+        "The creator channel has been set: {}",
+        describe_creator(&creator)
+    ))
+    .await?;
+    Ok(())
+}
+
+/// Remove a creator channel, channels created by it are kept.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn remove(
+    cx: Context<'_>,
+    #[description = "The creator channel"]
+    #[channel_types("Voice")]
+    channel: ChannelId,
+) -> Result<(), Error> {
+    let count = delete(voice_creators::table)
+        .filter(voice_creators::channel.eq(TryInto::<i64>::try_into(channel.get()).unwrap()))
+        .filter(
+            voice_creators::guild
+                .eq(TryInto::<i64>::try_into(cx.guild_id().unwrap().get()).unwrap()),
+        )
+        .execute(&mut cx.data().database.get()?)?;
+    cx.say(if count > 0 {
+        "The creator channel has been removed."
+    } else {
+        "This channel isn't a creator channel."
+    })
+    .await?;
+    Ok(())
+}
+
+/// List the creator channels of this server.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn list(cx: Context<'_>) -> Result<(), Error> {
+    let creators = voice_creators::table
+        .filter(
+            voice_creators::guild
+                .eq(TryInto::<i64>::try_into(cx.guild_id().unwrap().get()).unwrap()),
         )
-        .await?,
-    )
+        .order(voice_creators::created_at)
+        .select(VoiceCreator::as_select())
+        .load(&mut cx.data().database.get()?)?;
+    if creators.is_empty() {
+        cx.say("No creator channels have been added to this server.")
+            .await?;
+        return Ok(());
+    }
+    let mut content = String::from("Creator channels of this server:\n");
+    for creator in creators {
+        let line = format!("- {}\n", describe_creator(&creator));
+        if content.len() + line.len() > 1900 {
+            content.push_str("...");
+            break;
+        }
+        content.push_str(&line);
+    }
+    cx.say(content).await?;
+    Ok(())
+}
+
+/// Set how long owners have to be gone before their channel can be claimed.
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn set_claim_delay(
+    cx: Context<'_>,
+    #[description = "The delay (e.g. 10m), ignore this to reset it to 5m."] delay: Option<String>,
+) -> Result<(), Error> {
+    let mut delay = delay.unwrap_or(DEFAULT_CLAIM_DELAY.to_string());
+    if let Err(err) = parse_duration_to_seconds(&delay) {
+        cx.say(err).await?;
+        return Ok(());
+    }
+    if delay.chars().last().is_some_and(|c| c.is_numeric()) {
+        delay.push('s');
+    }
+    GuildSettings::set(
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        CLAIM_DELAY_SETTING,
+        Some(&delay),
+    )?;
+    cx.say(format!(
+        "Channels can be claimed **{}** after their owner has left.",
+        delay
+    ))
     .await?;
     Ok(())
 }
@@ -1,11 +1,14 @@
 use poise::Context as PoiseContext;
 use serenity::all::{
-    CreateActionRow, CreateInputText, CreateInteractionResponse, CreateModal, EditChannel, Member,
-    RoleId, User,
+    CreateActionRow, CreateInputText, CreateInteractionResponse, CreateModal, EditChannel,
+    GuildChannel, Member, MessageId, RoleId, User,
 };
 
 use crate::{
-    features::moderation::{flood_impl, inspect_impl, softban_impl, warning_impl},
+    features::{
+        moderation::{flood_impl, inspect_impl, softban_impl, warning_impl},
+        purge::{purge_impl, PurgeFilter},
+    },
     models::guild_settings::GuildSettings,
     util::parse_duration_to_seconds,
     Context, Error,
@@ -74,6 +77,80 @@ pub async fn context_menu_inspect(cx: Context<'_>, user: User) -> Result<(), Err
     Ok(())
 }
 
+fn parse_message_id(value: &str) -> Option<MessageId> {
+    value
+        .trim()
+        .rsplit('/')
+        .next()
+        .and_then(|x| x.parse().ok())
+        .filter(|x| *x != 0)
+        .map(MessageId::new)
+}
+
+/// Delete recent messages of a channel
+///
+/// Example Usage:
+/// `/purge 50 user:@someone links:true` - Deletes the last 50 messages of someone containing links.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    category = "Moderation",
+    required_bot_permissions = "MANAGE_MESSAGES | READ_MESSAGE_HISTORY",
+    default_member_permissions = "MANAGE_MESSAGES"
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn purge(
+    cx: Context<'_>,
+    #[description = "Number of messages to delete"]
+    #[min = 1]
+    #[max = 500]
+    count: usize,
+    #[description = "Only delete messages of this user"] user: Option<User>,
+    #[description = "Only delete messages of bots"] bots: Option<bool>,
+    #[description = "Only delete messages containing this text"] contains: Option<String>,
+    #[description = "Only delete messages with attachments"] attachments: Option<bool>,
+    #[description = "Only delete messages with links"] links: Option<bool>,
+    #[description = "Only delete messages before this message (ID or link)"] before: Option<String>,
+    #[description = "Only delete messages after this message (ID or link)"] after: Option<String>,
+    #[description = "Channel to operate on, defaults to current channel"]
+    #[channel_types("Text", "Voice", "News", "PublicThread", "PrivateThread")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let before = before.map(|x| parse_message_id(&x));
+    let after = after.map(|x| parse_message_id(&x));
+    if before.is_some_and(|x| x.is_none()) || after.is_some_and(|x| x.is_none()) {
+        cx.say("Invalid message").await?;
+        return Ok(());
+    }
+    cx.defer_ephemeral().await?;
+    let channel = match channel {
+        Some(channel) => channel.id,
+        None => cx.channel_id(),
+    };
+    let filter = PurgeFilter {
+        user: user.map(|x| x.id),
+        bots: bots.unwrap_or(false),
+        contains: contains.map(|x| x.to_lowercase()),
+        attachments: attachments.unwrap_or(false),
+        links: links.unwrap_or(false),
+        before: before.flatten(),
+        after: after.flatten(),
+    };
+    let content = purge_impl(
+        &cx,
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        channel,
+        cx.author(),
+        count,
+        filter,
+    )
+    .await?;
+    cx.say(content).await?;
+    Ok(())
+}
+
 /// Inspect a user
 #[poise::command(
     slash_command,
@@ -0,0 +1,51 @@
+use fang::AsyncQueueable;
+use serenity::all::Member;
+
+use crate::{
+    features::quarantine::{release_impl, ReleaseQuarantine},
+    Context, Error,
+};
+
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    subcommands("release"),
+    default_member_permissions = "MANAGE_ROLES",
+    required_bot_permissions = "MANAGE_ROLES"
+)]
+pub async fn quarantine(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Release a member from the new account quarantine early.
+#[poise::command(slash_command, ephemeral)]
+pub async fn release(
+    cx: Context<'_>,
+    #[description = "The quarantined member"] member: Member,
+    #[description = "Reason of the release"] reason: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = cx.guild_id().unwrap();
+    let actor = cx.author();
+    let reason = format!(
+        "Released by @{} ({}){}",
+        actor.name,
+        actor.id,
+        reason.map(|x| format!(": {}", x)).unwrap_or_default()
+    );
+    if !release_impl(&cx, &cx.data().database, guild_id, member.user.id, &reason).await? {
+        cx.say("Quarantine is disabled.").await?;
+        return Ok(());
+    }
+    if let Err(err) = cx
+        .data()
+        .queue
+        .remove_task_by_metadata(&ReleaseQuarantine::new(guild_id, member.user.id, 0))
+        .await
+    {
+        log::warn!("Unable to remove quarantine release task: {}", err);
+    }
+    cx.say(format!("<@{}> has been released.", member.user.id))
+        .await?;
+    Ok(())
+}
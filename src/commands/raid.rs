@@ -0,0 +1,69 @@
+use fang::AsyncQueueable;
+
+use crate::{
+    features::raid::{end_raid_mode, start_raid_mode, EndRaidMode},
+    Context, Error,
+};
+
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    subcommands("start", "end"),
+    default_member_permissions = "MANAGE_GUILD",
+    required_bot_permissions = "MANAGE_CHANNELS | MANAGE_ROLES"
+)]
+pub async fn raid(_cx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Start raid mode manually.
+#[poise::command(slash_command, ephemeral)]
+pub async fn start(cx: Context<'_>) -> Result<(), Error> {
+    let actor = cx.author();
+    cx.defer_ephemeral().await?;
+    cx.say(
+        start_raid_mode(
+            &cx,
+            (&cx.data().database, &cx.data().queue),
+            cx.guild_id().unwrap(),
+            &[],
+            &format!("Started by <@{}>.", actor.id),
+        )
+        .await?,
+    )
+    .await?;
+    Ok(())
+}
+
+/// End raid mode and revert the slowmode and quarantines.
+#[poise::command(slash_command, ephemeral)]
+pub async fn end(cx: Context<'_>) -> Result<(), Error> {
+    let guild_id = cx.guild_id().unwrap();
+    cx.defer_ephemeral().await?;
+    let res = match end_raid_mode(
+        &cx,
+        &cx.data().database,
+        guild_id,
+        &format!("Ended by <@{}>.", cx.author().id),
+    )
+    .await
+    {
+        Ok(res) => res,
+        Err(err) => {
+            // the scheduled task is kept to try again.
+            cx.say(err.to_string()).await?;
+            return Ok(());
+        }
+    };
+    if let Err(err) = cx
+        .data()
+        .queue
+        .remove_task_by_metadata(&EndRaidMode::new(guild_id, 0))
+        .await
+    {
+        log::warn!("Unable to remove raid mode task: {}", err);
+    }
+    cx.say(res).await?;
+    Ok(())
+}
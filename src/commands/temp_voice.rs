@@ -3,12 +3,20 @@ use diesel::{
     ExpressionMethods, OptionalExtension, RunQueryDsl, SelectableHelper,
 };
 use poise::CreateReply;
-use serenity::all::{
-    ChannelId, Colour, CreateActionRow, CreateEmbed, CreateSelectMenu, CreateSelectMenuKind,
-    CreateSelectMenuOption, EditChannel, ReactionType,
-};
+use serenity::all::{ChannelId, EditChannel, EditMember, Member};
 
-use crate::{models::voice_channel::VoiceChannel, schema::voice_channels, Context, Error};
+use crate::{
+    features::temp_voice::{
+        control_panel, get_owned_voice_channel, kick_menu, limit_impl, rename_impl,
+        reset_voice_preference, save_voice_preference, set_voice_channel_owner,
+        sync_voice_permissions, transfer_impl, update_privacy, VoicePreferenceChange,
+        CLAIM_DELAY_SETTING, DEFAULT_CLAIM_DELAY,
+    },
+    models::{guild_settings::GuildSettings, voice_channel::VoiceChannel},
+    schema::voice_channels,
+    util::parse_duration_to_seconds,
+    Context, Error,
+};
 
 async fn own_voice_channel_check(cx: Context<'_>) -> Result<bool, Error> {
     let Some(channel) = cx.guild_channel().await else {
@@ -36,8 +44,11 @@ async fn own_voice_channel_check(cx: Context<'_>) -> Result<bool, Error> {
     ephemeral,
     guild_only,
     rename = "tempvoice",
-    subcommands("rename", "limit", "delete", "kick"),
-    required_bot_permissions = "MANAGE_CHANNELS|MOVE_MEMBERS"
+    subcommands(
+        "rename", "limit", "bitrate", "region", "lock", "unlock", "hide", "unhide", "permit",
+        "reject", "reset", "transfer", "claim", "delete", "kick", "panel"
+    ),
+    required_bot_permissions = "MANAGE_CHANNELS|MANAGE_ROLES|MOVE_MEMBERS"
 )]
 pub async fn temp_voice(_cx: Context<'_>) -> Result<(), Error> {
     Ok(())
@@ -50,54 +61,407 @@ pub async fn rename(
     #[description = "New name of the voice channel"]
     #[rest]
     name: String,
+) -> Result<(), Error> {
+    let content = rename_impl(
+        &cx,
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        cx.channel_id(),
+        cx.author(),
+        &name,
+    )
+    .await?;
+    cx.say(content).await?;
+    Ok(())
+}
+
+/// Set your voice channel's max user count.
+#[poise::command(slash_command, guild_only, check = "own_voice_channel_check")]
+pub async fn limit(
+    cx: Context<'_>,
+    #[description = "Max user count of the channel, ignore to remove the limit."]
+    #[max = 99]
+    #[min = 1]
+    count: Option<u32>,
+) -> Result<(), Error> {
+    let content = limit_impl(
+        &cx,
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        cx.channel_id(),
+        cx.author().id,
+        count.unwrap_or(0),
+    )
+    .await?;
+    cx.say(content).await?;
+    Ok(())
+}
+
+/// Set your voice channel's bitrate.
+#[poise::command(slash_command, guild_only, check = "own_voice_channel_check")]
+pub async fn bitrate(
+    cx: Context<'_>,
+    #[description = "Bitrate in kbps, the maximum depends on the server's boost level."]
+    #[min = 8]
+    #[max = 384]
+    kbps: u32,
 ) -> Result<(), Error> {
     let mut channel = cx.guild_channel().await.unwrap();
-    let actor = cx.author();
-    let reason = format!("Renamed by @{} ({})", actor.name, actor.id);
     match channel
-        .edit(
-            &cx,
-            EditChannel::new().name(&name).audit_log_reason(&reason),
-        )
+        .edit(&cx, EditChannel::new().bitrate(kbps * 1000))
         .await
     {
         Ok(_) => {
-            cx.say(format!("The channel has been renamed to {}", name))
+            save_voice_preference(
+                &cx.data().database,
+                cx.guild_id().unwrap(),
+                cx.author().id,
+                VoicePreferenceChange::Bitrate(kbps * 1000),
+            )?;
+            cx.say(format!(
+                "The bitrate of the channel has been set to {}kbps",
+                kbps
+            ))
+            .await
+        }
+        Err(_) => {
+            cx.say("Failed to set the bitrate, it might be too high for this server.")
                 .await
         }
-        Err(_) => cx.say("Failed to rename the channel.").await,
     }?;
     Ok(())
 }
 
-/// Set your voice channel's max user count.
+/// Set your voice channel's region.
 #[poise::command(slash_command, guild_only, check = "own_voice_channel_check")]
-pub async fn limit(
+pub async fn region(
     cx: Context<'_>,
-    #[description = "Max user count of the channel, ignore to remove the limit."]
-    #[max = 99]
-    #[min = 1]
-    count: Option<u32>,
+    #[description = "Voice region ID (e.g. rotterdam), ignore to let Discord choose."]
+    region: Option<String>,
 ) -> Result<(), Error> {
+    let region = region.map(|x| x.trim().to_lowercase());
+    if let Some(region) = &region {
+        let regions: Vec<String> = cx
+            .http()
+            .get_voice_regions()
+            .await?
+            .into_iter()
+            .filter(|x| !x.deprecated && !x.custom)
+            .map(|x| x.id)
+            .collect();
+        if !regions.contains(region) {
+            cx.say(format!(
+                "Invalid region, available regions: {}",
+                regions
+                    .iter()
+                    .map(|x| format!("`{}`", x))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ))
+            .await?;
+            return Ok(());
+        }
+    }
     let mut channel = cx.guild_channel().await.unwrap();
     match channel
-        .edit(&cx, EditChannel::new().user_limit(count.unwrap_or(0)))
+        .edit(&cx, EditChannel::new().voice_region(region.clone()))
         .await
     {
         Ok(_) => {
+            save_voice_preference(
+                &cx.data().database,
+                cx.guild_id().unwrap(),
+                cx.author().id,
+                VoicePreferenceChange::Region(region.clone()),
+            )?;
             cx.say(format!(
-                "The user limit of the channel has been set to {}",
-                count
-                    .map(|x| x.to_string())
-                    .unwrap_or("unlimited".to_string())
+                "The region of the channel has been set to {}",
+                region.unwrap_or("automatic".to_string())
             ))
             .await
         }
-        Err(_) => cx.say("Failed to rename the channel.").await,
+        Err(_) => cx.say("Failed to set the region.").await,
     }?;
     Ok(())
 }
 
+/// Save a privacy setting of the author and apply it to their channel.
+async fn privacy_impl(
+    cx: Context<'_>,
+    change: VoicePreferenceChange,
+    content: &str,
+) -> Result<bool, Error> {
+    let updated = update_privacy(
+        &cx,
+        &cx.data().database,
+        cx.guild_id().unwrap(),
+        cx.channel_id(),
+        cx.author().id,
+        change,
+    )
+    .await?;
+    cx.say(if updated {
+        content
+    } else {
+        "Failed to update the permissions of the channel."
+    })
+    .await?;
+    Ok(updated)
+}
+
+/// Only let permitted members join your voice channel.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    check = "own_voice_channel_check"
+)]
+pub async fn lock(cx: Context<'_>) -> Result<(), Error> {
+    privacy_impl(
+        cx,
+        VoicePreferenceChange::Locked(true),
+        "The channel has been locked, only permitted members can join.",
+    )
+    .await?;
+    Ok(())
+}
+
+/// Let everyone join your voice channel again.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    check = "own_voice_channel_check"
+)]
+pub async fn unlock(cx: Context<'_>) -> Result<(), Error> {
+    privacy_impl(
+        cx,
+        VoicePreferenceChange::Locked(false),
+        "The channel has been unlocked.",
+    )
+    .await?;
+    Ok(())
+}
+
+/// Hide your voice channel from everyone but permitted members.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    check = "own_voice_channel_check"
+)]
+pub async fn hide(cx: Context<'_>) -> Result<(), Error> {
+    privacy_impl(
+        cx,
+        VoicePreferenceChange::Hidden(true),
+        "The channel has been hidden, only permitted members can see it.",
+    )
+    .await?;
+    Ok(())
+}
+
+/// Make your voice channel visible to everyone again.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    check = "own_voice_channel_check"
+)]
+pub async fn unhide(cx: Context<'_>) -> Result<(), Error> {
+    privacy_impl(
+        cx,
+        VoicePreferenceChange::Hidden(false),
+        "The channel is visible again.",
+    )
+    .await?;
+    Ok(())
+}
+
+/// Let someone see and join your voice channel even if it's locked or hidden.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    check = "own_voice_channel_check"
+)]
+pub async fn permit(
+    cx: Context<'_>,
+    #[description = "The member to let in"] member: Member,
+) -> Result<(), Error> {
+    if member.user.id == cx.author().id {
+        cx.say("Invalid member").await?;
+        return Ok(());
+    }
+    privacy_impl(
+        cx,
+        VoicePreferenceChange::Permit(member.user.id),
+        &format!("<@{}> can now join your channel.", member.user.id),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Kick someone from your voice channel and keep them out.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    check = "own_voice_channel_check"
+)]
+pub async fn reject(
+    cx: Context<'_>,
+    #[description = "The member to keep out"] mut member: Member,
+) -> Result<(), Error> {
+    if member.user.id == cx.author().id {
+        cx.say("Invalid member").await?;
+        return Ok(());
+    }
+    let rejected = privacy_impl(
+        cx,
+        VoicePreferenceChange::Reject(member.user.id),
+        &format!("<@{}> can no longer join your channel.", member.user.id),
+    )
+    .await?;
+    let channel = cx.guild_channel().await.unwrap();
+    if rejected
+        && channel
+            .members(cx)?
+            .iter()
+            .any(|x| x.user.id == member.user.id)
+    {
+        let actor = cx.author();
+        let reason = format!("Rejected by @{} ({})", actor.name, actor.id);
+        member
+            .edit(
+                cx,
+                EditMember::new()
+                    .disconnect_member()
+                    .audit_log_reason(&reason),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Forget the settings applied to the voice channels you create.
+#[poise::command(slash_command, ephemeral, guild_only)]
+pub async fn reset(cx: Context<'_>) -> Result<(), Error> {
+    let pool = &cx.data().database;
+    let guild_id = cx.guild_id().unwrap();
+    let reset = reset_voice_preference(pool, guild_id, cx.author().id)?;
+    // the privacy settings are the only ones kept in sync with the current channel.
+    if let Some(channel) = get_owned_voice_channel(pool, guild_id, cx.author().id)? {
+        let channel = ChannelId::new(channel.id.try_into().unwrap());
+        if let Err(err) = sync_voice_permissions(&cx, pool, guild_id, channel, cx.author().id).await
+        {
+            log::warn!("Failed to update temp voice permissions: {err:?}");
+        }
+    }
+    cx.say(if reset {
+        "Your voice channel settings have been reset, new channels will use the defaults."
+    } else {
+        "You don't have any saved voice channel settings."
+    })
+    .await?;
+    Ok(())
+}
+
+/// Give the ownership of your voice channel to someone in it.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    check = "own_voice_channel_check"
+)]
+pub async fn transfer(
+    cx: Context<'_>,
+    #[description = "The new owner of the channel"] member: Member,
+) -> Result<(), Error> {
+    let channel = cx.guild_channel().await.unwrap();
+    let content = transfer_impl(
+        &cx,
+        &cx.data().database,
+        &channel,
+        cx.author().id,
+        &member.user,
+    )
+    .await?;
+    cx.say(content).await?;
+    Ok(())
+}
+
+/// Become the owner of this voice channel after its owner has left.
+#[poise::command(slash_command, ephemeral, guild_only)]
+pub async fn claim(cx: Context<'_>) -> Result<(), Error> {
+    let Some(channel) = cx.guild_channel().await else {
+        return Ok(());
+    };
+    let pool = &cx.data().database;
+    let Some(voice_channel) = voice_channels::table
+        .find(TryInto::<i64>::try_into(channel.id.get()).unwrap())
+        .select(VoiceChannel::as_select())
+        .first(&mut pool.get()?)
+        .optional()?
+    else {
+        cx.say("This voice channel is not managed by temp voice.")
+            .await?;
+        return Ok(());
+    };
+    let author = cx.author().id;
+    if voice_channel.creator == TryInto::<i64>::try_into(author.get()).unwrap() {
+        cx.say("You already own this channel.").await?;
+        return Ok(());
+    }
+    let members = channel.members(cx)?;
+    if !members.iter().any(|x| x.user.id == author) {
+        cx.say("You have to be in this channel to claim it.")
+            .await?;
+        return Ok(());
+    }
+    if members
+        .iter()
+        .any(|x| TryInto::<i64>::try_into(x.user.id.get()).unwrap() == voice_channel.creator)
+    {
+        cx.say("The owner is still in this channel.").await?;
+        return Ok(());
+    }
+    let delay = GuildSettings::get(pool, cx.guild_id().unwrap(), CLAIM_DELAY_SETTING)
+        .unwrap_or(DEFAULT_CLAIM_DELAY.to_string());
+    // channels the owner left while the bot was offline have no time recorded, they are claimable.
+    if let Some(left_at) = voice_channel.owner_left_at {
+        let claimable_at =
+            left_at.and_utc().timestamp() + parse_duration_to_seconds(&delay)? as i64;
+        if claimable_at > chrono::Utc::now().timestamp() {
+            cx.say(format!(
+                "The owner left recently, you can claim this channel <t:{}:R>.",
+                claimable_at
+            ))
+            .await?;
+            return Ok(());
+        }
+    }
+    if get_owned_voice_channel(pool, channel.guild_id, author)?.is_some() {
+        cx.say("You already own a voice channel.").await?;
+        return Ok(());
+    }
+    set_voice_channel_owner(pool, channel.id, author)?;
+    if let Err(err) = sync_voice_permissions(&cx, pool, channel.guild_id, channel.id, author).await
+    {
+        log::warn!("Failed to update temp voice permissions: {err:?}");
+    }
+    channel
+        .say(
+            cx,
+            format!(
+                "<@{}> claimed this channel, <@{}> is no longer the owner.",
+                author, voice_channel.creator
+            ),
+        )
+        .await?;
+    cx.say("You are now the owner of this channel.").await?;
+    Ok(())
+}
+
 /// Delete your voice channel.
 #[poise::command(
     slash_command,
@@ -120,35 +484,14 @@ pub async fn delete(cx: Context<'_>) -> Result<(), Error> {
 )]
 pub async fn kick(cx: Context<'_>) -> Result<(), Error> {
     let channel = cx.guild_channel().await.unwrap();
-    let options: Vec<CreateSelectMenuOption> = channel
-        .members(cx)?
-        .iter()
-        .filter(|x| x.user.id != cx.author().id)
-        .map(|x| {
-            CreateSelectMenuOption::new(
-                x.nick.clone().unwrap_or(x.user.display_name().to_string()),
-                x.user.id.get().to_string(),
-            )
-            .description(x.user.name.clone())
-            .emoji(ReactionType::Unicode("👤".to_string()))
-        })
-        .collect();
-    if options.is_empty() {
+    let Some((embed, menu)) = kick_menu(&channel.members(cx)?, cx.author().id) else {
         cx.say("There's no one else to be kicked.").await?;
         return Ok(());
-    }
-    let select_menu =
-        CreateSelectMenu::new("voice_kick_user", CreateSelectMenuKind::String { options })
-            .placeholder("The user that will be kicked.");
+    };
     let reply = CreateReply {
         ephemeral: Some(true),
-        embeds: vec![CreateEmbed::new()
-            .color(Colour::ORANGE)
-            .title("Kick a User")
-            .description(
-                "To kick a user from your voice channel, select him/her in the following menu.",
-            )],
-        components: Some(vec![CreateActionRow::SelectMenu(select_menu)]),
+        embeds: vec![embed],
+        components: Some(vec![menu]),
         ..Default::default()
     };
     cx.send(reply).await?;
@@ -156,6 +499,21 @@ pub async fn kick(cx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
+/// Post the control panel of your voice channel.
+#[poise::command(
+    slash_command,
+    ephemeral,
+    guild_only,
+    check = "own_voice_channel_check"
+)]
+pub async fn panel(cx: Context<'_>) -> Result<(), Error> {
+    cx.channel_id()
+        .send_message(cx, control_panel(cx.author().id))
+        .await?;
+    cx.say("The control panel has been posted.").await?;
+    Ok(())
+}
+
 /// Force delete a temporary voice channel.
 #[poise::command(
     slash_command,
@@ -4,6 +4,7 @@ use crate::models::moderation_log::ModerationLog;
 
 impl From<ModerationLog> for CreateEmbed {
     fn from(value: ModerationLog) -> Self {
+        let active = value.is_active();
         let mut embed = value
             .kind
             .create_embed()
@@ -29,6 +30,13 @@ impl From<ModerationLog> for CreateEmbed {
                 true,
             ),
         ]);
+        if let Some(expires_at) = value.expires_at {
+            embed = embed.field(
+                if active { "Expires" } else { "Expired" },
+                format!("<t:{}:R>", expires_at.and_utc().timestamp()),
+                true,
+            );
+        }
         embed
     }
 }
@@ -1,20 +1,37 @@
 use diesel::{delete, ExpressionMethods, RunQueryDsl};
 use serenity::{
     all::{
-        ActivityData, AuditLogEntry, ChannelId, ChannelType, Context, EventHandler, GuildChannel,
-        GuildId, Interaction, Message, MessageId, MessageUpdateEvent, Ready, VoiceState,
+        ActionExecution, ActivityData, AuditLogEntry, ChannelId, ChannelType, Context,
+        EventHandler, GuildChannel, GuildId, Interaction, Member, Message, MessageId,
+        MessageUpdateEvent, Ready, VoiceState,
     },
     async_trait,
 };
 
 use crate::{
-    features::{message_change_log, moderation, moderation_log, temp_voice},
+    features::{
+        anti_spam, attachment_archive, filter, invite_filter, mention_spam, message_change_log,
+        message_store, moderation, moderation_log, phishing, quarantine, raid, temp_voice,
+    },
     schema::voice_channels,
     util::get_pool_from_serenity,
 };
 
 pub struct Handler;
 
+/// Run the automod checks from the most to the least severe and stop after the first one that
+/// acts, so a message isn't deleted and punished several times.
+async fn handle_automod(cx: Context, msg: Message) {
+    if phishing::handle_message(cx.clone(), msg.clone()).await
+        || filter::handle_message(cx.clone(), msg.clone()).await
+        || invite_filter::handle_message(cx.clone(), msg.clone()).await
+        || mention_spam::handle_message(cx.clone(), msg.clone()).await
+    {
+        return;
+    }
+    anti_spam::handle_message(cx, msg).await;
+}
+
 #[async_trait]
 impl EventHandler for Handler {
     async fn ready(&self, cx: Context, ready: Ready) {
@@ -32,13 +49,21 @@ impl EventHandler for Handler {
         );
     }
 
+    async fn cache_ready(&self, cx: Context, _guilds: Vec<GuildId>) {
+        // channels could have been left or deleted while the bot was offline.
+        let pool = get_pool_from_serenity(&cx).await;
+        if let Err(err) = temp_voice::reconcile_channels(&cx, &pool).await {
+            log::warn!("Unable to reconcile temporary voice channels: {err:?}");
+        }
+    }
+
     async fn channel_delete(
         &self,
         cx: Context,
         channel: GuildChannel,
         _messages: Option<Vec<Message>>,
     ) {
-        if channel.kind == ChannelType::Voice {
+        if matches!(channel.kind, ChannelType::Voice | ChannelType::Stage) {
             // try delete voice channel record.
             if let Ok(mut conn) = get_pool_from_serenity(&cx).await.get() {
                 delete(voice_channels::table)
@@ -62,6 +87,30 @@ impl EventHandler for Handler {
         ));
     }
 
+    async fn auto_moderation_action_execution(&self, cx: Context, execution: ActionExecution) {
+        tokio::spawn(moderation_log::handle_auto_moderation_action(cx, execution));
+    }
+
+    async fn guild_member_addition(&self, cx: Context, new_member: Member) {
+        tokio::spawn(quarantine::handle_member_addition(
+            cx.clone(),
+            new_member.clone(),
+        ));
+        tokio::spawn(raid::handle_member_addition(cx, new_member));
+    }
+
+    async fn message(&self, cx: Context, new_message: Message) {
+        tokio::spawn(message_store::handle_message(
+            cx.clone(),
+            new_message.clone(),
+        ));
+        tokio::spawn(attachment_archive::handle_message(
+            cx.clone(),
+            new_message.clone(),
+        ));
+        tokio::spawn(handle_automod(cx, new_message));
+    }
+
     async fn message_delete(
         &self,
         cx: Context,
@@ -99,6 +148,11 @@ impl EventHandler for Handler {
         new: Option<Message>,
         event: MessageUpdateEvent,
     ) {
+        tokio::spawn(invite_filter::handle_message_update(
+            cx.clone(),
+            new.clone(),
+            event.clone(),
+        ));
         tokio::spawn(message_change_log::handle_message_update(
             cx,
             old_if_available,
@@ -115,7 +169,7 @@ impl EventHandler for Handler {
         tokio::spawn(moderation::handle_interaction(cx, interaction));
     }
 
-    async fn voice_state_update(&self, cx: Context, _old: Option<VoiceState>, new: VoiceState) {
-        tokio::spawn(temp_voice::handle_voice_state_update(cx, new));
+    async fn voice_state_update(&self, cx: Context, old: Option<VoiceState>, new: VoiceState) {
+        tokio::spawn(temp_voice::handle_voice_state_update(cx, old, new));
     }
 }
@@ -1,8 +1,23 @@
+pub mod anti_spam;
+pub mod attachment_archive;
 pub mod case;
+pub mod filter;
+pub mod ghost_ping;
+pub mod invite_filter;
+pub mod lockdown;
+pub mod member_log;
+pub mod mention_spam;
 pub mod message_change_log;
+pub mod message_store;
 pub mod moderation;
 pub mod moderation_dm;
 pub mod moderation_log;
+pub mod phishing;
+pub mod purge;
+pub mod quarantine;
+pub mod raid;
 pub mod temp_role;
 pub mod temp_voice;
 pub mod temp_warning;
+pub mod transcript;
+pub mod word_diff;
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use serenity::all::{ChannelId, Context, GuildId, Message, MessageId, RoleId, User, UserId};
+
+use crate::{
+    data::QueueKey, models::guild_settings::GuildSettings, util::get_pool_from_serenity,
+    ConnectionPool,
+};
+
+use super::moderation::flood_impl;
+
+pub const INTERVAL_SETTING: &str = "anti_spam_interval";
+pub const MESSAGES_SETTING: &str = "anti_spam_messages";
+pub const DUPLICATES_SETTING: &str = "anti_spam_duplicates";
+pub const DURATION_SETTING: &str = "anti_spam_flood_duration";
+pub const DELETE_SETTING: &str = "anti_spam_delete";
+pub const EXEMPT_ROLES_SETTING: &str = "anti_spam_exempt_roles";
+
+/// Clean up users that stopped sending messages once there are this many tracked users.
+const MAX_TRACKED_USERS: usize = 10_000;
+
+struct RecentMessage {
+    id: MessageId,
+    channel_id: ChannelId,
+    timestamp: i64,
+    content: String,
+    attachments: Vec<(String, u32)>,
+}
+
+lazy_static! {
+    static ref RECENT_MESSAGES: DashMap<(GuildId, UserId), Vec<RecentMessage>> = DashMap::new();
+}
+
+pub fn get_exempt_roles<G: Into<GuildId>>(pool: &ConnectionPool, guild: G) -> Vec<RoleId> {
+    GuildSettings::get_ids(pool, guild, EXEMPT_ROLES_SETTING)
+        .into_iter()
+        .map(RoleId::new)
+        .collect()
+}
+
+fn get_number<G: Into<GuildId>>(pool: &ConnectionPool, guild: G, key: &str) -> Option<usize> {
+    GuildSettings::get(pool, guild, key)
+        .and_then(|x| x.parse().ok())
+        .filter(|x| *x > 0)
+}
+
+/// Check the latest message against the user's recent messages.
+fn find_violation(
+    history: &[RecentMessage],
+    interval: i64,
+    max_messages: Option<usize>,
+    max_duplicates: Option<usize>,
+) -> Option<String> {
+    if max_messages.is_some_and(|x| history.len() >= x) {
+        return Some(format!(
+            "Sending {} messages in {} seconds",
+            history.len(),
+            interval
+        ));
+    }
+    let max_duplicates = max_duplicates?;
+    let latest = history.last()?;
+    if !latest.content.is_empty() {
+        let count = history
+            .iter()
+            .filter(|x| x.content == latest.content)
+            .count();
+        if count >= max_duplicates {
+            return Some(format!("Sending the same message {} times", count));
+        }
+    }
+    for attachment in &latest.attachments {
+        let count = history
+            .iter()
+            .filter(|x| x.attachments.contains(attachment))
+            .count();
+        if count >= max_duplicates {
+            return Some(format!("Sending the same attachment {} times", count));
+        }
+    }
+    None
+}
+
+pub async fn handle_message(cx: Context, msg: Message) -> bool {
+    let Some(guild_id) = msg.guild_id else {
+        return false;
+    };
+    if msg.author.bot || msg.webhook_id.is_some() {
+        return false;
+    }
+    let pool = get_pool_from_serenity(&cx).await;
+    let Some(interval) = get_number(&pool, guild_id, INTERVAL_SETTING) else {
+        return false;
+    };
+    let interval = interval as i64;
+    if let Some(member) = &msg.member {
+        let exempt_roles = get_exempt_roles(&pool, guild_id);
+        if member.roles.iter().any(|x| exempt_roles.contains(x)) {
+            return false;
+        }
+    }
+    let now = msg.timestamp.timestamp();
+    if RECENT_MESSAGES.len() > MAX_TRACKED_USERS {
+        RECENT_MESSAGES
+            .retain(|_, history| history.last().is_some_and(|x| x.timestamp > now - interval));
+    }
+    let max_messages = get_number(&pool, guild_id, MESSAGES_SETTING);
+    let max_duplicates = get_number(&pool, guild_id, DUPLICATES_SETTING);
+    let (reason, burst) = {
+        let mut history = RECENT_MESSAGES
+            .entry((guild_id, msg.author.id))
+            .or_default();
+        history.retain(|x| x.timestamp > now - interval);
+        history.push(RecentMessage {
+            id: msg.id,
+            channel_id: msg.channel_id,
+            timestamp: now,
+            content: msg.content.clone(),
+            attachments: msg
+                .attachments
+                .iter()
+                .map(|x| (x.filename.clone(), x.size))
+                .collect(),
+        });
+        let Some(reason) = find_violation(&history, interval, max_messages, max_duplicates) else {
+            return false;
+        };
+        (reason, std::mem::take(&mut *history))
+    };
+    if GuildSettings::get(&pool, guild_id, DELETE_SETTING).is_some() {
+        let mut channels: HashMap<ChannelId, Vec<MessageId>> = HashMap::new();
+        for message in &burst {
+            channels
+                .entry(message.channel_id)
+                .or_default()
+                .push(message.id);
+        }
+        for (channel, messages) in channels {
+            if let Err(err) = channel.delete_messages(&cx, messages).await {
+                log::warn!("Unable to delete spam messages: {err}");
+            }
+        }
+    }
+    let Ok(member) = guild_id.member(&cx, msg.author.id).await else {
+        return true;
+    };
+    let queue = cx.data.read().await.get::<QueueKey>().unwrap().clone();
+    let bot: User = cx.cache.current_user().clone().into();
+    let duration =
+        GuildSettings::get(&pool, guild_id, DURATION_SETTING).unwrap_or("10m".to_string());
+    match flood_impl(
+        &cx,
+        (&pool, &queue),
+        msg.channel_id,
+        member,
+        &bot,
+        duration,
+        Some(format!("Anti-spam: {}", reason)),
+    )
+    .await
+    {
+        Ok(res) => log::info!("Anti-spam flooded {}: {}", msg.author.id, res),
+        Err(err) => log::warn!("Unable to flood spammer: {err}"),
+    }
+    true
+}
@@ -0,0 +1,165 @@
+use std::{collections::HashMap, env, sync::Arc};
+
+use lazy_static::lazy_static;
+use object_store::{aws::AmazonS3Builder, local::LocalFileSystem, path::Path, ObjectStore};
+use serenity::all::{Attachment, Context, CreateAttachment, GuildId, Message, MessageId};
+
+use crate::{
+    models::guild_settings::GuildSettings, util::get_pool_from_serenity, ConnectionPool, Error,
+};
+
+use super::message_store::RETENTION_SETTING;
+
+pub const MAX_SIZE_SETTING: &str = "attachment_archive_max_size";
+
+/// Discord's upload limit for bots, larger attachments can't be re-uploaded to the logs anyway.
+pub const MAX_ARCHIVE_SIZE_MB: u32 = 25;
+
+lazy_static! {
+    static ref ARCHIVE: Option<Arc<dyn ObjectStore>> = create_archive();
+}
+
+fn create_archive() -> Option<Arc<dyn ObjectStore>> {
+    if let Ok(bucket) = env::var("ATTACHMENT_ARCHIVE_S3_BUCKET") {
+        // credentials, region and endpoint are read from the AWS_* variables,
+        // set AWS_ENDPOINT and AWS_ALLOW_HTTP to use a local MinIO.
+        return match AmazonS3Builder::from_env().with_bucket_name(bucket).build() {
+            Ok(store) => Some(Arc::new(store)),
+            Err(err) => {
+                log::error!("Unable to create S3 attachment archive: {err}");
+                None
+            }
+        };
+    }
+    if let Ok(path) = env::var("ATTACHMENT_ARCHIVE_PATH") {
+        if let Err(err) = std::fs::create_dir_all(&path) {
+            log::error!("Unable to create attachment archive directory: {err}");
+            return None;
+        }
+        return match LocalFileSystem::new_with_prefix(path) {
+            Ok(store) => Some(Arc::new(store)),
+            Err(err) => {
+                log::error!("Unable to create local attachment archive: {err}");
+                None
+            }
+        };
+    }
+    None
+}
+
+pub fn is_available() -> bool {
+    ARCHIVE.is_some()
+}
+
+fn attachment_path(guild: GuildId, message: MessageId, attachment: &Attachment) -> Path {
+    Path::from(format!("{}/{}/{}", guild, message, attachment.id))
+}
+
+pub async fn handle_message(cx: Context, msg: Message) {
+    let Some(archive) = ARCHIVE.as_ref() else {
+        return;
+    };
+    let Some(guild_id) = msg.guild_id else {
+        return;
+    };
+    if msg.attachments.is_empty() {
+        return;
+    }
+    let Some(max_size) = GuildSettings::get(
+        &get_pool_from_serenity(&cx).await,
+        guild_id,
+        MAX_SIZE_SETTING,
+    )
+    .and_then(|x| x.parse::<u32>().ok()) else {
+        return;
+    };
+    for attachment in &msg.attachments {
+        if attachment.size > max_size {
+            continue;
+        }
+        let data = match attachment.download().await {
+            Ok(data) => data,
+            Err(err) => {
+                log::warn!("Unable to download attachment for archiving: {err}");
+                continue;
+            }
+        };
+        if let Err(err) = archive
+            .put(&attachment_path(guild_id, msg.id, attachment), data.into())
+            .await
+        {
+            log::warn!("Unable to archive attachment: {err}");
+        }
+    }
+}
+
+/// Get archived copies of the attachments, attachments that weren't archived are skipped.
+pub async fn fetch_attachments<G: Into<GuildId>>(
+    guild: G,
+    message: MessageId,
+    attachments: &[Attachment],
+) -> Vec<CreateAttachment> {
+    let Some(archive) = ARCHIVE.as_ref() else {
+        return vec![];
+    };
+    let guild = guild.into();
+    let mut files = vec![];
+    for attachment in attachments {
+        let Ok(result) = archive
+            .get(&attachment_path(guild, message, attachment))
+            .await
+        else {
+            continue;
+        };
+        match result.bytes().await {
+            Ok(bytes) => files.push(CreateAttachment::bytes(
+                bytes.to_vec(),
+                attachment.filename.clone(),
+            )),
+            Err(err) => log::warn!("Unable to read archived attachment: {err}"),
+        }
+    }
+    files
+}
+
+/// Delete archived attachments of messages that are older than the message store retention.
+pub async fn prune_attachments(pool: &ConnectionPool) -> Result<usize, Error> {
+    let Some(archive) = ARCHIVE.as_ref() else {
+        return Ok(0);
+    };
+    let retentions: HashMap<u64, i64> = GuildSettings::all(pool, RETENTION_SETTING)?
+        .into_iter()
+        .filter_map(|(guild, retention)| Some((guild.get(), retention.parse().ok()?)))
+        .collect();
+    let now = chrono::Utc::now().timestamp();
+    let mut count = 0;
+    for guild in archive.list_with_delimiter(None).await?.common_prefixes {
+        let retention = guild
+            .filename()
+            .and_then(|x| x.parse::<u64>().ok())
+            .and_then(|x| retentions.get(&x));
+        for message in archive
+            .list_with_delimiter(Some(&guild))
+            .await?
+            .common_prefixes
+        {
+            let expired = match retention {
+                Some(retention) => message
+                    .filename()
+                    .and_then(|x| x.parse::<u64>().ok())
+                    .filter(|x| *x != 0)
+                    .is_none_or(|x| MessageId::new(x).created_at().timestamp() < now - retention),
+                // the guild no longer has the message store enabled.
+                None => true,
+            };
+            if !expired {
+                continue;
+            }
+            for object in archive.list_with_delimiter(Some(&message)).await?.objects {
+                archive.delete(&object.location).await?;
+                count += 1;
+            }
+        }
+    }
+    Ok(count)
+}
@@ -0,0 +1,276 @@
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use decancer::Options;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use lazy_static::lazy_static;
+use regex::{Regex, RegexBuilder};
+use serenity::all::{
+    ChannelId, Colour, Context, CreateEmbed, CreateEmbedFooter, CreateMessage, GuildId, Message,
+    RoleId, User,
+};
+
+use crate::{
+    data::QueueKey,
+    models::{
+        filter_rule::{FilterAction, FilterRule, FilterRuleKind},
+        guild_settings::GuildSettings,
+    },
+    schema::filter_rules,
+    util::get_pool_from_serenity,
+    ConnectionPool, Error,
+};
+
+use super::moderation::{flood_impl, warning_impl};
+
+const DEFAULT_FLOOD_DURATION: &str = "10m";
+/// Keep user supplied regexes from compiling into something huge.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+pub struct CompiledRule {
+    pub rule: FilterRule,
+    regex: Regex,
+}
+
+impl CompiledRule {
+    pub fn new(rule: FilterRule) -> Result<Self, regex::Error> {
+        let regex = compile_pattern(rule.kind, &rule.pattern)?;
+        Ok(CompiledRule { rule, regex })
+    }
+
+    /// Match against the normalized content, regexes are matched against the original content too.
+    pub fn is_match(&self, content: &str, normalized: &str) -> bool {
+        self.regex.is_match(normalized)
+            || (self.rule.kind == FilterRuleKind::Regex && self.regex.is_match(content))
+    }
+
+    fn is_exempt(&self, channels: &[ChannelId], roles: &[RoleId]) -> bool {
+        channels.iter().any(|x| {
+            self.rule
+                .exempt_channels
+                .contains(&x.get().try_into().unwrap())
+        }) || roles.iter().any(|x| {
+            self.rule
+                .exempt_roles
+                .contains(&x.get().try_into().unwrap())
+        })
+    }
+}
+
+lazy_static! {
+    static ref RULES: DashMap<GuildId, Arc<Vec<CompiledRule>>> = DashMap::new();
+}
+
+/// Map confusable characters (e.g. "𝓯𝓻𝓮𝓮 ℕ𝕚𝕥𝕣𝕠") to plain lowercase text.
+pub fn normalize(text: &str) -> String {
+    decancer::cure(text, Options::default())
+        .map(|x| x.to_string())
+        .unwrap_or(text.to_lowercase())
+}
+
+/// Convert a glob to a regex, `*` matches any characters and `?` matches one character
+/// within a word.
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::new();
+    for ch in glob.chars() {
+        match ch {
+            '*' => regex.push_str(r"\S*"),
+            '?' => regex.push_str(r"\S"),
+            ch => regex.push_str(&regex::escape(&ch.to_string())),
+        }
+    }
+    format!(r"(?:^|\s){}(?:$|\s)", regex)
+}
+
+pub fn compile_pattern(kind: FilterRuleKind, pattern: &str) -> Result<Regex, regex::Error> {
+    let pattern = match kind {
+        FilterRuleKind::Word => format!(r"\b{}\b", regex::escape(&normalize(pattern))),
+        FilterRuleKind::Glob => glob_to_regex(&normalize(pattern)),
+        FilterRuleKind::Regex => pattern.to_string(),
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+}
+
+pub fn get_rules<G: Into<GuildId>>(
+    pool: &ConnectionPool,
+    guild: G,
+) -> Result<Arc<Vec<CompiledRule>>, Error> {
+    let guild = guild.into();
+    if let Some(rules) = RULES.get(&guild) {
+        return Ok(rules.clone());
+    }
+    let rules: Vec<FilterRule> = filter_rules::table
+        .filter(filter_rules::guild.eq(TryInto::<i64>::try_into(guild.get()).unwrap()))
+        .order(filter_rules::created_at.asc())
+        .select(FilterRule::as_select())
+        .load(&mut pool.get()?)?;
+    let rules = Arc::new(
+        rules
+            .into_iter()
+            .filter_map(|rule| match CompiledRule::new(rule) {
+                Ok(rule) => Some(rule),
+                Err(err) => {
+                    log::warn!("Unable to compile filter rule: {err}");
+                    None
+                }
+            })
+            .collect::<Vec<CompiledRule>>(),
+    );
+    RULES.insert(guild, rules.clone());
+    Ok(rules)
+}
+
+/// Forget the cached rules of the guild after they have been changed.
+pub fn invalidate_rules<G: Into<GuildId>>(guild: G) {
+    RULES.remove(&guild.into());
+}
+
+/// Find the most severe rule the content matches.
+fn find_match<'a>(
+    rules: &'a [CompiledRule],
+    content: &str,
+    channels: &[ChannelId],
+    roles: &[RoleId],
+) -> Option<&'a CompiledRule> {
+    let normalized = normalize(content);
+    rules
+        .iter()
+        .filter(|x| !x.is_exempt(channels, roles) && x.is_match(content, &normalized))
+        .max_by_key(|x| x.rule.action)
+}
+
+async fn send_filter_log(
+    cx: &Context,
+    pool: &ConnectionPool,
+    guild_id: GuildId,
+    msg: &Message,
+    rule: &FilterRule,
+) {
+    let Some(channel) = GuildSettings::get(pool, guild_id, "moderation_log_channel")
+        .map(|x| ChannelId::new(x.parse().unwrap()))
+    else {
+        return;
+    };
+    let content: String = msg.content.chars().take(1024).collect();
+    let embed = CreateEmbed::new()
+        .color(Colour::DARK_ORANGE)
+        .title("🧹 Filtered Message")
+        .fields([
+            ("User", format!("<@{}>", msg.author.id), true),
+            ("Channel", format!("<#{}>", msg.channel_id), true),
+            ("Action", rule.action.description().to_string(), true),
+            (
+                "Rule",
+                format!("{:?} `{}`", rule.kind, rule.pattern.replace('`', "'")),
+                false,
+            ),
+            ("Content", content, false),
+        ])
+        .footer(CreateEmbedFooter::new(format!("Rule ID: {}", rule.id)));
+    if let Err(err) = channel
+        .send_message(cx, CreateMessage::new().embed(embed))
+        .await
+    {
+        log::warn!("Unable to send filter log: {err:?}");
+    }
+}
+
+pub async fn handle_message(cx: Context, msg: Message) -> bool {
+    let Some(guild_id) = msg.guild_id else {
+        return false;
+    };
+    if msg.author.bot || msg.webhook_id.is_some() || msg.content.is_empty() {
+        return false;
+    }
+    let pool = get_pool_from_serenity(&cx).await;
+    let rules = match get_rules(&pool, guild_id) {
+        Ok(rules) => rules,
+        Err(err) => {
+            log::warn!("Unable to get filter rules: {err}");
+            return false;
+        }
+    };
+    if rules.is_empty() {
+        return false;
+    }
+    let mut channels = vec![msg.channel_id];
+    if let Some(parent) = cx.cache.guild(guild_id).and_then(|guild| {
+        guild
+            .channels
+            .get(&msg.channel_id)
+            .or(guild.threads.iter().find(|x| x.id == msg.channel_id))
+            .and_then(|x| x.parent_id)
+    }) {
+        channels.push(parent);
+    }
+    let roles = msg
+        .member
+        .as_ref()
+        .map(|x| x.roles.clone())
+        .unwrap_or_default();
+    let Some(matched) = find_match(&rules, &msg.content, &channels, &roles) else {
+        return false;
+    };
+    let rule = matched.rule.clone();
+    if let Err(err) = msg.delete(&cx).await {
+        log::warn!("Unable to delete filtered message: {err}");
+    }
+    send_filter_log(&cx, &pool, guild_id, &msg, &rule).await;
+    if rule.action == FilterAction::Delete {
+        return true;
+    }
+    let Ok(member) = guild_id.member(&cx, msg.author.id).await else {
+        return true;
+    };
+    let queue = cx.data.read().await.get::<QueueKey>().unwrap().clone();
+    let bot: User = cx.cache.current_user().clone().into();
+    let reason = Some("Sending a message that matched the content filter".to_string());
+    let res = match rule.action {
+        FilterAction::Warn => {
+            warning_impl(
+                &cx,
+                (&pool, &queue),
+                msg.channel_id,
+                member,
+                &bot,
+                reason,
+                rule.duration,
+            )
+            .await
+        }
+        _ => {
+            flood_impl(
+                &cx,
+                (&pool, &queue),
+                msg.channel_id,
+                member,
+                &bot,
+                rule.duration.unwrap_or(DEFAULT_FLOOD_DURATION.to_string()),
+                reason,
+            )
+            .await
+        }
+    };
+    if let Err(err) = res {
+        log::warn!("Unable to punish filtered message: {err}");
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_pattern() {
+        let word = compile_pattern(FilterRuleKind::Word, "free").unwrap();
+        assert!(word.is_match(&normalize("get 𝓯𝓻𝓮𝓮 stuff")));
+        assert!(!word.is_match(&normalize("freedom")));
+        let glob = compile_pattern(FilterRuleKind::Glob, "nitro*").unwrap();
+        assert!(glob.is_match(&normalize("claim nitroGift now")));
+        assert!(!glob.is_match(&normalize("no nitrate")));
+    }
+}
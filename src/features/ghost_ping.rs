@@ -0,0 +1,144 @@
+use serenity::all::{
+    ChannelId, Colour, Context, CreateEmbed, CreateEmbedFooter, CreateMessage, GuildId, Message,
+    RoleId, UserId,
+};
+
+use crate::{models::guild_settings::GuildSettings, util::get_pool_from_serenity, ConnectionPool};
+
+pub const WINDOW_SETTING: &str = "ghost_ping_window";
+pub const NOTICE_CHANNEL_SETTING: &str = "ghost_ping_notice_channel";
+
+/// Collect the mentions of the message, messages rebuilt from the message store only have
+/// the content so the mentions are parsed from it as well.
+fn find_mentions(msg: &Message) -> (Vec<UserId>, Vec<RoleId>) {
+    let mut users: Vec<UserId> = msg.mentions.iter().map(|x| x.id).collect();
+    let mut roles = msg.mention_roles.clone();
+    for part in msg.content.split('<').skip(1) {
+        let Some(end) = part.find('>') else {
+            continue;
+        };
+        let mention = format!("<{}>", &part[..end]);
+        if let Some(role) = serenity::utils::parse_role_mention(&mention) {
+            roles.push(role);
+        } else if let Some(user) = serenity::utils::parse_user_mention(&mention) {
+            users.push(user);
+        }
+    }
+    users.retain(|x| *x != msg.author.id);
+    users.sort();
+    users.dedup();
+    roles.sort();
+    roles.dedup();
+    (users, roles)
+}
+
+async fn send_notice(
+    cx: &Context,
+    pool: &ConnectionPool,
+    guild_id: GuildId,
+    msg: &Message,
+    users: Vec<UserId>,
+    roles: Vec<RoleId>,
+    edited: bool,
+) {
+    if users.is_empty() && roles.is_empty() {
+        return;
+    }
+    let channel = GuildSettings::get(pool, guild_id, NOTICE_CHANNEL_SETTING)
+        .map(|x| ChannelId::new(x.parse().unwrap()))
+        .unwrap_or(msg.channel_id);
+    let mentioned = users
+        .iter()
+        .map(|x| format!("<@{}>", x))
+        .chain(roles.iter().map(|x| format!("<@&{}>", x)))
+        .collect::<Vec<String>>()
+        .join(" ");
+    let embed = CreateEmbed::new()
+        .color(Colour::DARK_PURPLE)
+        .title("👻 Ghost Ping")
+        .description(if edited {
+            "The mentions were edited out of the message."
+        } else {
+            "The message was deleted after mentioning."
+        })
+        .fields([
+            ("Pinged by", format!("<@{}>", msg.author.id), true),
+            ("Channel", format!("<#{}>", msg.channel_id), true),
+            ("Mentioned", mentioned, false),
+        ])
+        .footer(CreateEmbedFooter::new(format!("ID: {}", msg.id)));
+    if let Err(err) = channel
+        .send_message(cx, CreateMessage::new().embed(embed))
+        .await
+    {
+        log::warn!("Unable to send ghost ping notice: {err:?}");
+    }
+}
+
+fn get_window(pool: &ConnectionPool, guild_id: GuildId) -> Option<i64> {
+    GuildSettings::get(pool, guild_id, WINDOW_SETTING).and_then(|x| x.parse().ok())
+}
+
+/// Whether deleting the message could be a ghost ping, checked before looking up who deleted it
+/// since that needs the audit log.
+pub fn is_candidate(pool: &ConnectionPool, guild_id: GuildId, msg: &Message) -> bool {
+    if msg.author.bot {
+        return false;
+    }
+    let Some(window) = get_window(pool, guild_id) else {
+        return false;
+    };
+    if chrono::Utc::now().timestamp() - msg.timestamp.timestamp() > window {
+        return false;
+    }
+    let (users, roles) = find_mentions(msg);
+    !users.is_empty() || !roles.is_empty()
+}
+
+/// Report the deleted message, `deleter` is `None` when the author deleted it.
+///
+/// Only the author can ghost ping, messages deleted by moderators, automod or `/purge` are
+/// skipped. Bulk deletions are never passed here since only moderators and bots can make them.
+pub async fn handle_message_delete(
+    cx: Context,
+    guild_id: GuildId,
+    msg: Message,
+    deleter: Option<UserId>,
+) {
+    if deleter.is_some_and(|x| x != msg.author.id) {
+        return;
+    }
+    let pool = get_pool_from_serenity(&cx).await;
+    if !is_candidate(&pool, guild_id, &msg) {
+        return;
+    }
+    let (users, roles) = find_mentions(&msg);
+    send_notice(&cx, &pool, guild_id, &msg, users, roles, false).await;
+}
+
+pub async fn handle_message_update(cx: Context, guild_id: GuildId, old: Message, new: Message) {
+    if new.author.bot {
+        return;
+    }
+    let Some(edited_timestamp) = new.edited_timestamp else {
+        return;
+    };
+    let pool = get_pool_from_serenity(&cx).await;
+    let Some(window) = get_window(&pool, guild_id) else {
+        return;
+    };
+    if edited_timestamp.timestamp() - old.timestamp.timestamp() > window {
+        return;
+    }
+    let (old_users, old_roles) = find_mentions(&old);
+    let (new_users, new_roles) = find_mentions(&new);
+    let users = old_users
+        .into_iter()
+        .filter(|x| !new_users.contains(x))
+        .collect();
+    let roles = old_roles
+        .into_iter()
+        .filter(|x| !new_roles.contains(x))
+        .collect();
+    send_notice(&cx, &pool, guild_id, &new, users, roles, true).await;
+}
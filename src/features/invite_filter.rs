@@ -0,0 +1,188 @@
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use regex::Regex;
+use serenity::all::{
+    Context, GuildId, HttpError, Invite, Message, MessageUpdateEvent, RoleId, User,
+};
+
+use crate::{data::QueueKey, models::guild_settings::GuildSettings, util::get_pool_from_serenity};
+
+use super::moderation::warning_impl;
+
+pub const ENABLED_SETTING: &str = "invite_filter";
+pub const ALLOWED_GUILDS_SETTING: &str = "invite_filter_allowed_guilds";
+pub const PARTNER_ROLES_SETTING: &str = "invite_filter_partner_roles";
+
+/// Clear resolved invites once this many are cached.
+const MAX_CACHED_INVITES: usize = 10_000;
+/// Invites can be deleted or point elsewhere later, resolve them again after this long.
+const INVITE_CACHE_SECS: i64 = 3600;
+
+lazy_static! {
+    static ref INVITE_REGEX: Regex = Regex::new(
+        r"(?i)(discord(?:app)?\.com/invite|discord\.gg|discord\.(?:io|me|li)|dsc\.gg|invite\.gg)/([a-z0-9-]+)"
+    )
+    .unwrap();
+    /// When each invite code was resolved and the guild it leads to, `None` for invites that
+    /// don't exist.
+    static ref RESOLVED_INVITES: DashMap<String, (i64, Option<GuildId>)> = DashMap::new();
+}
+
+/// Find the invite codes in the content, invites of third-party services can't be resolved
+/// and are returned without a code.
+fn find_invites(content: &str) -> Vec<Option<String>> {
+    INVITE_REGEX
+        .captures_iter(content)
+        .map(|x| {
+            let host = x[1].to_lowercase();
+            if host == "discord.gg" || host.ends_with("/invite") {
+                Some(x[2].to_string())
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Resolve the guild the invite leads to, `None` for invites that don't exist or don't lead to
+/// a guild. Failed lookups, e.g. rate limits, are returned as errors and not cached.
+async fn resolve_invite(cx: &Context, code: &str) -> Result<Option<GuildId>, serenity::Error> {
+    let now = chrono::Utc::now().timestamp();
+    if let Some(entry) = RESOLVED_INVITES.get(code) {
+        if entry.0 > now - INVITE_CACHE_SECS {
+            return Ok(entry.1);
+        }
+    }
+    let guild = match Invite::get(cx, code, false, false, None).await {
+        Ok(invite) => invite.guild.map(|x| x.id),
+        // Unknown Invite
+        Err(serenity::Error::Http(HttpError::UnsuccessfulRequest(err)))
+            if err.error.code == 10006 =>
+        {
+            None
+        }
+        Err(err) => return Err(err),
+    };
+    if RESOLVED_INVITES.len() > MAX_CACHED_INVITES {
+        RESOLVED_INVITES.retain(|_, x| x.0 > now - INVITE_CACHE_SECS);
+    }
+    if RESOLVED_INVITES.len() > MAX_CACHED_INVITES {
+        RESOLVED_INVITES.clear();
+    }
+    RESOLVED_INVITES.insert(code.to_string(), (now, guild));
+    Ok(guild)
+}
+
+/// Returns whether the message was deleted for posting an invite.
+async fn check_message(cx: &Context, guild_id: GuildId, msg: &Message, roles: &[RoleId]) -> bool {
+    if msg.author.bot || msg.webhook_id.is_some() {
+        return false;
+    }
+    let pool = get_pool_from_serenity(cx).await;
+    if GuildSettings::get(&pool, guild_id, ENABLED_SETTING).is_none() {
+        return false;
+    }
+    let invites = find_invites(&msg.content);
+    if invites.is_empty() {
+        return false;
+    }
+    let partner_roles = GuildSettings::get_ids(&pool, guild_id, PARTNER_ROLES_SETTING);
+    if roles.iter().any(|x| partner_roles.contains(&x.get())) {
+        return false;
+    }
+    let allowed_guilds = GuildSettings::get_ids(&pool, guild_id, ALLOWED_GUILDS_SETTING);
+    let mut violation = false;
+    for code in invites {
+        let guild = match code {
+            Some(code) => match resolve_invite(cx, &code).await {
+                Ok(guild) => guild,
+                Err(err) => {
+                    // don't punish anyone for Discord being unavailable.
+                    log::warn!("Unable to resolve invite {code}: {err}");
+                    continue;
+                }
+            },
+            None => None,
+        };
+        if !guild.is_some_and(|x| x == guild_id || allowed_guilds.contains(&x.get())) {
+            violation = true;
+            break;
+        }
+    }
+    if !violation {
+        return false;
+    }
+    if let Err(err) = msg.delete(cx).await {
+        log::warn!("Unable to delete invite: {err}");
+    }
+    let Ok(member) = guild_id.member(cx, msg.author.id).await else {
+        return true;
+    };
+    let queue = cx.data.read().await.get::<QueueKey>().unwrap().clone();
+    let bot: User = cx.cache.current_user().clone().into();
+    if let Err(err) = warning_impl(
+        cx,
+        (&pool, &queue),
+        msg.channel_id,
+        member,
+        &bot,
+        Some("Posting an invite to another server".to_string()),
+        None,
+    )
+    .await
+    {
+        log::warn!("Unable to warn for posting an invite: {err}");
+    }
+    true
+}
+
+pub async fn handle_message(cx: Context, msg: Message) -> bool {
+    let Some(guild_id) = msg.guild_id else {
+        return false;
+    };
+    let roles = msg
+        .member
+        .as_ref()
+        .map(|x| x.roles.clone())
+        .unwrap_or_default();
+    check_message(&cx, guild_id, &msg, &roles).await
+}
+
+pub async fn handle_message_update(cx: Context, new: Option<Message>, event: MessageUpdateEvent) {
+    let Some(guild_id) = event.guild_id else {
+        return;
+    };
+    if event.content.is_none() {
+        // the content isn't changed.
+        return;
+    }
+    let msg = match new {
+        Some(msg) => msg,
+        None => match event.channel_id.message(&cx, event.id).await {
+            Ok(msg) => msg,
+            Err(_) => return,
+        },
+    };
+    if find_invites(&msg.content).is_empty() {
+        return;
+    }
+    let roles = match guild_id.member(&cx, msg.author.id).await {
+        Ok(member) => member.roles,
+        Err(_) => return,
+    };
+    check_message(&cx, guild_id, &msg, &roles).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_invites() {
+        assert_eq!(
+            find_invites("join discord.gg/abc and https://discord.com/invite/x-y or discord.me/z"),
+            vec![Some("abc".to_string()), Some("x-y".to_string()), None]
+        );
+        assert!(find_invites("discord.com/channels/1/2").is_empty());
+    }
+}
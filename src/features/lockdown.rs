@@ -0,0 +1,198 @@
+use diesel::{delete, insert_into, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use fang::{async_trait, typetag, AsyncQueueable, AsyncRunnable, FangError, Scheduled};
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    CacheHttp, ChannelId, EditChannel, GuildChannel, GuildId, PermissionOverwrite,
+    PermissionOverwriteType, Permissions, RoleId,
+};
+
+use crate::{
+    acquire_cache_http, acquire_pool,
+    models::lockdown_overwrite::{CreateLockdownOverwrite, LockdownOverwrite},
+    schema::lockdown_overwrites,
+    ConnectionPool, Error,
+};
+
+/// Permissions denied to the locked roles.
+pub const LOCKED_PERMISSIONS: Permissions =
+    Permissions::SEND_MESSAGES.union(Permissions::SEND_MESSAGES_IN_THREADS);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "fang::serde")]
+pub struct UnlockChannel {
+    guild_id: u64,
+    channel_id: u64,
+    #[serde(skip)]
+    duration: u64,
+}
+
+impl UnlockChannel {
+    pub fn new<G: Into<GuildId>, C: Into<ChannelId>>(guild: G, channel: C, duration: u64) -> Self {
+        UnlockChannel {
+            guild_id: guild.into().get(),
+            channel_id: channel.into().get(),
+            duration,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl AsyncRunnable for UnlockChannel {
+    async fn run(&self, _queue: &dyn AsyncQueueable) -> Result<(), FangError> {
+        unlock_channel(
+            &acquire_cache_http(),
+            &acquire_pool(),
+            ChannelId::new(self.channel_id),
+            "Lockdown expired",
+        )
+        .await
+        .map_err(|x| FangError {
+            description: x.to_string(),
+        })?;
+        Ok(())
+    }
+
+    fn uniq(&self) -> bool {
+        true
+    }
+
+    fn cron(&self) -> Option<Scheduled> {
+        Some(Scheduled::ScheduleOnce(
+            chrono::Utc::now() + std::time::Duration::from_secs(self.duration),
+        ))
+    }
+
+    fn max_retries(&self) -> i32 {
+        3
+    }
+}
+
+/// Deny [`LOCKED_PERMISSIONS`] to the roles in the channel, saving their previous overwrites.
+/// Returns whether anything changed, roles that are already locked are left as is.
+pub async fn lock_channel<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    channel: &GuildChannel,
+    roles: &[RoleId],
+    reason: &str,
+) -> Result<bool, Error> {
+    let mut overwrites = channel.permission_overwrites.clone();
+    let mut snapshots = vec![];
+    for role in roles {
+        let position = overwrites
+            .iter()
+            .position(|x| x.kind == PermissionOverwriteType::Role(*role));
+        let previous = position.map(|x| overwrites[x].clone());
+        if previous
+            .as_ref()
+            .is_some_and(|x| x.deny.contains(LOCKED_PERMISSIONS))
+        {
+            continue;
+        }
+        snapshots.push(CreateLockdownOverwrite::new(
+            channel.guild_id,
+            channel.id,
+            role.get(),
+            previous.as_ref(),
+            Some(reason.to_string()),
+        ));
+        let (allow, deny) = previous
+            .map(|x| (x.allow, x.deny))
+            .unwrap_or((Permissions::empty(), Permissions::empty()));
+        let overwrite = PermissionOverwrite {
+            allow: allow - LOCKED_PERMISSIONS,
+            deny: deny | LOCKED_PERMISSIONS,
+            kind: PermissionOverwriteType::Role(*role),
+        };
+        match position {
+            Some(x) => overwrites[x] = overwrite,
+            None => overwrites.push(overwrite),
+        }
+    }
+    if snapshots.is_empty() {
+        return Ok(false);
+    }
+    // save the snapshots first so the previous overwrites can't get lost, keep the earliest
+    // snapshot if the channel was locked and restored outside the bot.
+    let channel_id: i64 = channel.id.get().try_into().unwrap();
+    let inserted: Vec<i64> = insert_into(lockdown_overwrites::table)
+        .values(snapshots)
+        .on_conflict_do_nothing()
+        .returning(lockdown_overwrites::role)
+        .get_results(&mut pool.get()?)?;
+    if let Err(err) = channel
+        .id
+        .edit(
+            cx,
+            EditChannel::new()
+                .permissions(overwrites)
+                .audit_log_reason(reason),
+        )
+        .await
+    {
+        delete(lockdown_overwrites::table)
+            .filter(lockdown_overwrites::channel.eq(channel_id))
+            .filter(lockdown_overwrites::role.eq_any(inserted))
+            .execute(&mut pool.get()?)?;
+        return Err(err.into());
+    }
+    Ok(true)
+}
+
+/// Restore the overwrites saved by [`lock_channel`], returns whether the channel was locked.
+pub async fn unlock_channel<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    channel: ChannelId,
+    reason: &str,
+) -> Result<bool, Error> {
+    let channel_id: i64 = channel.get().try_into().unwrap();
+    let snapshots: Vec<LockdownOverwrite> = lockdown_overwrites::table
+        .filter(lockdown_overwrites::channel.eq(channel_id))
+        .select(LockdownOverwrite::as_select())
+        .load(&mut pool.get()?)?;
+    if snapshots.is_empty() {
+        return Ok(false);
+    }
+    // the channel may be gone, in which case there is nothing to restore.
+    if let Some(current) = channel.to_channel(cx).await.ok().and_then(|x| x.guild()) {
+        let mut overwrites = current.permission_overwrites;
+        for snapshot in &snapshots {
+            overwrites.retain(|x| x.kind != PermissionOverwriteType::Role(snapshot.role_id()));
+            if let Some(previous) = snapshot.previous() {
+                overwrites.push(previous);
+            }
+        }
+        channel
+            .edit(
+                cx,
+                EditChannel::new()
+                    .permissions(overwrites)
+                    .audit_log_reason(reason),
+            )
+            .await?;
+    }
+    delete(lockdown_overwrites::table)
+        .filter(lockdown_overwrites::channel.eq(channel_id))
+        .execute(&mut pool.get()?)?;
+    Ok(true)
+}
+
+/// Channels of the guild that are currently locked.
+pub fn get_locked_channels<G: Into<GuildId>>(
+    pool: &ConnectionPool,
+    guild: G,
+) -> Result<Vec<ChannelId>, Error> {
+    let channels: Vec<i64> = lockdown_overwrites::table
+        .filter(
+            lockdown_overwrites::guild.eq(TryInto::<i64>::try_into(guild.into().get()).unwrap()),
+        )
+        .select(lockdown_overwrites::channel)
+        .distinct()
+        .load(&mut pool.get()?)?;
+    Ok(channels
+        .into_iter()
+        .map(|x| ChannelId::new(x.try_into().unwrap()))
+        .collect())
+}
@@ -0,0 +1,25 @@
+use serenity::all::{CacheHttp, ChannelId, CreateEmbed, CreateMessage, GuildId};
+
+use crate::{models::guild_settings::GuildSettings, ConnectionPool};
+
+pub const CHANNEL_SETTING: &str = "member_log_channel";
+
+/// Post the embed to the member log channel if there is one.
+pub async fn send_member_log<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    embed: CreateEmbed,
+) {
+    let Some(channel) = GuildSettings::get(pool, guild, CHANNEL_SETTING)
+        .map(|x| ChannelId::new(x.parse().unwrap()))
+    else {
+        return;
+    };
+    if let Err(err) = channel
+        .send_message(cx, CreateMessage::new().embed(embed))
+        .await
+    {
+        log::warn!("Unable to send member log: {err:?}");
+    }
+}
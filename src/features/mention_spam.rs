@@ -0,0 +1,169 @@
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use serenity::all::{Context, GuildId, Message, User, UserId};
+
+use crate::{
+    data::QueueKey, models::guild_settings::GuildSettings, util::get_pool_from_serenity,
+    ConnectionPool,
+};
+
+use super::moderation::{flood_impl, softban_impl, timeout_impl};
+
+pub const MESSAGE_LIMIT_SETTING: &str = "mention_limit";
+pub const WINDOW_LIMIT_SETTING: &str = "mention_window_limit";
+pub const WINDOW_SETTING: &str = "mention_window";
+pub const ACTION_SETTING: &str = "mention_action";
+pub const DURATION_SETTING: &str = "mention_action_duration";
+
+const DEFAULT_DURATION: &str = "1h";
+/// Audit log reasons can't be longer than 512 characters.
+const MAX_REASON_LENGTH: usize = 512;
+/// Clean up users that stopped mentioning once there are this many tracked users.
+const MAX_TRACKED_USERS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum MentionAction {
+    #[name = "timeout"]
+    Timeout,
+    #[name = "flood"]
+    Flood,
+    #[name = "softban"]
+    Softban,
+}
+
+impl MentionAction {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::Flood => "flood",
+            Self::Softban => "softban",
+        }
+    }
+
+    fn from_setting<G: Into<GuildId>>(pool: &ConnectionPool, guild: G) -> Self {
+        match GuildSettings::get(pool, guild, ACTION_SETTING).as_deref() {
+            Some("flood") => Self::Flood,
+            Some("softban") => Self::Softban,
+            _ => Self::Timeout,
+        }
+    }
+}
+
+struct RecentMentions {
+    timestamp: i64,
+    mentions: Vec<String>,
+}
+
+lazy_static! {
+    static ref RECENT_MENTIONS: DashMap<(GuildId, UserId), Vec<RecentMentions>> = DashMap::new();
+}
+
+fn get_number<G: Into<GuildId>>(pool: &ConnectionPool, guild: G, key: &str) -> Option<usize> {
+    GuildSettings::get(pool, guild, key)
+        .and_then(|x| x.parse().ok())
+        .filter(|x| *x > 0)
+}
+
+/// Build the case reason with the mentions as evidence, cutting off mentions that don't fit.
+fn create_reason(mentions: &[String]) -> String {
+    let mut reason = format!("Mentioning {} users or roles:", mentions.len());
+    for (i, mention) in mentions.iter().enumerate() {
+        let suffix = format!(" and {} more", mentions.len() - i);
+        if reason.len() + mention.len() + 1 + suffix.len() > MAX_REASON_LENGTH {
+            reason.push_str(&suffix);
+            break;
+        }
+        reason.push(' ');
+        reason.push_str(mention);
+    }
+    reason
+}
+
+pub async fn handle_message(cx: Context, msg: Message) -> bool {
+    let Some(guild_id) = msg.guild_id else {
+        return false;
+    };
+    if msg.author.bot || msg.webhook_id.is_some() {
+        return false;
+    }
+    let mut mentions: Vec<String> = msg
+        .mentions
+        .iter()
+        .filter(|x| x.id != msg.author.id)
+        .map(|x| format!("<@{}>", x.id))
+        .chain(msg.mention_roles.iter().map(|x| format!("<@&{}>", x)))
+        .collect();
+    mentions.sort();
+    mentions.dedup();
+    if mentions.is_empty() {
+        return false;
+    }
+    let pool = get_pool_from_serenity(&cx).await;
+    let message_limit = get_number(&pool, guild_id, MESSAGE_LIMIT_SETTING);
+    let window_limit = get_number(&pool, guild_id, WINDOW_LIMIT_SETTING);
+    let window = get_number(&pool, guild_id, WINDOW_SETTING).unwrap_or_default() as i64;
+    let violation = if message_limit.is_some_and(|x| mentions.len() > x) {
+        Some(mentions)
+    } else if let (Some(window_limit), true) = (window_limit, window > 0) {
+        let now = msg.timestamp.timestamp();
+        if RECENT_MENTIONS.len() > MAX_TRACKED_USERS {
+            RECENT_MENTIONS
+                .retain(|_, history| history.last().is_some_and(|x| x.timestamp > now - window));
+        }
+        let mut history = RECENT_MENTIONS
+            .entry((guild_id, msg.author.id))
+            .or_default();
+        history.retain(|x| x.timestamp > now - window);
+        history.push(RecentMentions {
+            timestamp: now,
+            mentions,
+        });
+        let mut unique: Vec<String> = history.iter().flat_map(|x| x.mentions.clone()).collect();
+        unique.sort();
+        unique.dedup();
+        if unique.len() > window_limit {
+            history.clear();
+            Some(unique)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+    let Some(mentions) = violation else {
+        return false;
+    };
+    if let Err(err) = msg.delete(&cx).await {
+        log::warn!("Unable to delete mass mention: {err}");
+    }
+    let Ok(member) = guild_id.member(&cx, msg.author.id).await else {
+        return true;
+    };
+    let reason = Some(create_reason(&mentions));
+    let duration = GuildSettings::get(&pool, guild_id, DURATION_SETTING)
+        .unwrap_or(DEFAULT_DURATION.to_string());
+    let bot: User = cx.cache.current_user().clone().into();
+    let res = match MentionAction::from_setting(&pool, guild_id) {
+        MentionAction::Timeout => timeout_impl(&cx, member, duration, reason).await,
+        MentionAction::Flood => {
+            let queue = cx.data.read().await.get::<QueueKey>().unwrap().clone();
+            flood_impl(
+                &cx,
+                (&pool, &queue),
+                msg.channel_id,
+                member,
+                &bot,
+                duration,
+                reason,
+            )
+            .await
+        }
+        MentionAction::Softban => {
+            softban_impl(&cx, &pool, msg.channel_id, member, &bot, reason).await
+        }
+    };
+    if let Err(err) = res {
+        log::warn!("Unable to punish mass mention: {err}");
+    }
+    true
+}
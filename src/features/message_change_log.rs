@@ -1,9 +1,357 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use dashmap::DashMap;
+use lazy_static::lazy_static;
 use serenity::all::{
-    ChannelId, Colour, Context, CreateEmbed, CreateEmbedFooter, CreateMessage, GuildId, Message,
-    MessageId, MessageUpdateEvent, StickerFormatType,
+    audit_log::Action, AuditLogEntry, AuditLogEntryId, ChannelId, Colour, Context,
+    CreateAttachment, CreateEmbed, CreateEmbedFooter, CreateMessage, GuildId, Message,
+    MessageAction, MessageId, MessageUpdateEvent, StickerFormatType, UserId,
+};
+
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+
+use crate::{
+    models::{
+        guild_settings::GuildSettings,
+        message_log_ignore::{MessageLogIgnore, MessageLogIgnoreKind},
+    },
+    schema::message_log_ignores,
+    util::get_pool_from_serenity,
+    ConnectionPool, Error,
+};
+
+use super::{
+    attachment_archive::{fetch_attachments, MAX_ARCHIVE_SIZE_MB},
+    ghost_ping, message_store,
+    transcript::create_transcript_attachments,
+    word_diff::{render_word_diff, tokenize},
 };
 
-use crate::{models::guild_settings::GuildSettings, util::get_pool_from_serenity};
+const BULK_DELETE_MAX_AUTHORS: usize = 40;
+/// Discord's upload limit for bots, for all the files of a message together.
+const MAX_UPLOAD_SIZE: usize = MAX_ARCHIVE_SIZE_MB as usize * 1024 * 1024;
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+const EMBED_FIELD_LIMIT: usize = 1024;
+/// Content that needs more embeds than this is attached as a file instead.
+const MAX_CONTENT_EMBEDS: usize = 3;
+/// Discord may take a moment to write the audit log entry after the message is deleted.
+const AUDIT_LOG_DELAY: Duration = Duration::from_secs(2);
+/// Entries created this recently are new even if we haven't seen them before.
+const AUDIT_LOG_WINDOW_SECS: i64 = 10;
+
+lazy_static! {
+    /// How many deletions of each audit log entry have been attributed so far. Discord groups
+    /// deletions of the same author in the same channel into one entry and only bumps its count.
+    static ref ATTRIBUTED_DELETIONS: Mutex<HashMap<AuditLogEntryId, u64>> =
+        Mutex::new(HashMap::new());
+    /// The last page of message deletion entries of each guild and when it was requested,
+    /// deletions in the same burst share one request.
+    static ref AUDIT_LOG_PAGES: DashMap<GuildId, AuditLogPage> = DashMap::new();
+}
+
+type AuditLogPage = Arc<tokio::sync::Mutex<Option<(Instant, Arc<Vec<AuditLogEntry>>)>>>;
+
+/// The message deletion entries of the guild, requested after the deletion is old enough to
+/// show up in the audit log.
+async fn fetch_deletion_entries(
+    cx: &Context,
+    guild_id: GuildId,
+    deleted_at: Instant,
+) -> Result<Arc<Vec<AuditLogEntry>>, Error> {
+    let page = AUDIT_LOG_PAGES.entry(guild_id).or_default().clone();
+    let mut page = page.lock().await;
+    if let Some((requested_at, entries)) = &*page {
+        if *requested_at >= deleted_at + AUDIT_LOG_DELAY {
+            return Ok(entries.clone());
+        }
+    }
+    let requested_at = Instant::now();
+    let logs = guild_id
+        .audit_logs(
+            cx,
+            Some(Action::Message(MessageAction::Delete)),
+            None,
+            None,
+            Some(25),
+        )
+        .await?;
+    let entries = Arc::new(logs.entries);
+    *page = Some((requested_at, entries.clone()));
+    Ok(entries)
+}
+
+/// Find the moderator who deleted the message, `None` means the author deleted it.
+///
+/// This requires the View Audit Log permission.
+async fn find_deleter(
+    cx: &Context,
+    guild_id: GuildId,
+    channel_id: ChannelId,
+    author: UserId,
+) -> Result<Option<UserId>, Error> {
+    let deleted_at = Instant::now();
+    tokio::time::sleep(AUDIT_LOG_DELAY).await;
+    let entries = fetch_deletion_entries(cx, guild_id, deleted_at).await?;
+    let now = chrono::Utc::now().timestamp();
+    let mut attributed = ATTRIBUTED_DELETIONS.lock().unwrap();
+    // Discord stops grouping deletions into old entries, no need to keep them.
+    attributed.retain(|id, _| id.created_at().timestamp() > now - 3600);
+    for entry in entries.iter() {
+        let Some(options) = &entry.options else {
+            continue;
+        };
+        if entry.target_id.map(|x| x.get()) != Some(author.get())
+            || options.channel_id != Some(channel_id)
+        {
+            continue;
+        }
+        let count = options.count.unwrap_or(1);
+        let claimed = attributed.entry(entry.id).or_insert_with(|| {
+            if entry.id.created_at().timestamp() > now - AUDIT_LOG_WINDOW_SECS {
+                0
+            } else {
+                // we don't know how many of the deletions happened before we saw the entry.
+                count
+            }
+        });
+        if *claimed < count {
+            *claimed += 1;
+            return Ok(Some(entry.user_id));
+        }
+    }
+    Ok(None)
+}
+
+/// List the URLs for an embed field, the URLs that don't fit are counted instead.
+fn render_url_field(urls: Vec<String>) -> String {
+    let total = urls.len();
+    let mut value = String::new();
+    let mut value_len = 0;
+    for (i, url) in urls.into_iter().enumerate() {
+        let remaining = total - i;
+        let url_len = url.chars().count() + usize::from(i > 0);
+        // the last URL doesn't need room for the count.
+        let reserved = if remaining > 1 {
+            format!("\n...and {} more", remaining).len()
+        } else {
+            0
+        };
+        if value_len + url_len + reserved > EMBED_FIELD_LIMIT {
+            value.push_str(&format!("\n...and {} more", remaining));
+            break;
+        }
+        if i > 0 {
+            value.push('\n');
+        }
+        value.push_str(&url);
+        value_len += url_len;
+    }
+    value
+}
+
+/// Split the pieces into chunks that fit in embed descriptions, pieces are only split when
+/// a single piece doesn't fit.
+fn split_content(pieces: Vec<String>) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut current = String::new();
+    let mut current_len = 0;
+    for piece in pieces {
+        let piece_len = piece.chars().count();
+        if current_len + piece_len > EMBED_DESCRIPTION_LIMIT && current_len > 0 {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+        if piece_len <= EMBED_DESCRIPTION_LIMIT {
+            current.push_str(&piece);
+            current_len += piece_len;
+            continue;
+        }
+        let chars: Vec<char> = piece.chars().collect();
+        for part in chars.chunks(EMBED_DESCRIPTION_LIMIT) {
+            if part.len() == EMBED_DESCRIPTION_LIMIT {
+                chunks.push(part.iter().collect());
+            } else {
+                current = part.iter().collect();
+                current_len = part.len();
+            }
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Group the files so each group fits in the upload limit of a single message.
+fn group_files(files: Vec<CreateAttachment>) -> Vec<Vec<CreateAttachment>> {
+    let mut groups: Vec<Vec<CreateAttachment>> = vec![];
+    let mut size = 0;
+    for file in files {
+        if groups.is_empty() || size + file.data.len() > MAX_UPLOAD_SIZE {
+            groups.push(vec![]);
+            size = 0;
+        }
+        size += file.data.len();
+        groups.last_mut().unwrap().push(file);
+    }
+    groups
+}
+
+/// Create the log messages with the content as the embed description.
+///
+/// Long content continues in more embeds, each in its own message since a message can't have
+/// more than 6000 characters in embeds. Even longer content is attached as a file. Files that
+/// don't fit in the upload limit of the first message are sent in the following messages.
+fn create_log_messages<F: FnOnce() -> CreateAttachment>(
+    embed: CreateEmbed,
+    colour: Colour,
+    content: Vec<String>,
+    files: Vec<CreateAttachment>,
+    content_file: F,
+) -> Vec<CreateMessage> {
+    let chunks = split_content(content);
+    let mut messages = vec![];
+    let mut files = if chunks.len() > MAX_CONTENT_EMBEDS {
+        messages.push(
+            CreateMessage::new()
+                .embed(embed.description("_The content is too long, see the attached file._")),
+        );
+        group_files([content_file()].into_iter().chain(files).collect())
+    } else {
+        let mut chunks = chunks.into_iter();
+        messages
+            .push(CreateMessage::new().embed(embed.description(chunks.next().unwrap_or_default())));
+        messages.extend(
+            chunks.map(|x| {
+                CreateMessage::new().embed(CreateEmbed::new().colour(colour).description(x))
+            }),
+        );
+        group_files(files)
+    }
+    .into_iter();
+    if let Some(first) = files.next() {
+        messages[0] = std::mem::take(&mut messages[0]).add_files(first);
+    }
+    messages.extend(files.map(|x| CreateMessage::new().add_files(x)));
+    messages
+}
+
+async fn send_log_messages(cx: &Context, log_channel: ChannelId, messages: Vec<CreateMessage>) {
+    for message in messages {
+        if let Err(err) = log_channel.send_message(cx, message).await {
+            log::warn!("Unable to send message change log: {err:?}");
+            return;
+        }
+    }
+}
+
+async fn find_deleted_message(
+    cx: &Context,
+    pool: &ConnectionPool,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) -> Option<Message> {
+    let cached_msg = cx.cache.message(channel_id, message_id).map(|x| x.clone());
+    match cached_msg {
+        Some(msg) => Some(msg),
+        // the cache doesn't have it, try the message store.
+        None => message_store::fetch_message(cx, pool, message_id).await,
+    }
+}
+
+pub fn get_ignores<G: Into<GuildId>>(pool: &ConnectionPool, guild: G) -> Vec<MessageLogIgnore> {
+    let Ok(mut conn) = pool.get() else {
+        return vec![];
+    };
+    message_log_ignores::table
+        .filter(
+            message_log_ignores::guild.eq(TryInto::<i64>::try_into(guild.into().get()).unwrap()),
+        )
+        .select(MessageLogIgnore::as_select())
+        .load(&mut conn)
+        .unwrap_or_default()
+}
+
+/// Check the channel, its parent channel for threads and its category.
+async fn is_channel_ignored(
+    cx: &Context,
+    ignores: &[MessageLogIgnore],
+    channel_id: ChannelId,
+) -> bool {
+    if !ignores.iter().any(|x| {
+        matches!(
+            x.kind,
+            MessageLogIgnoreKind::Channel | MessageLogIgnoreKind::Category
+        )
+    }) {
+        return false;
+    }
+    let mut current = Some(channel_id);
+    while let Some(id) = current {
+        if ignores.iter().any(|x| {
+            x.matches(MessageLogIgnoreKind::Channel, id.get())
+                || x.matches(MessageLogIgnoreKind::Category, id.get())
+        }) {
+            return true;
+        }
+        current = id
+            .to_channel(cx)
+            .await
+            .ok()
+            .and_then(|x| x.guild())
+            .and_then(|x| x.parent_id);
+    }
+    false
+}
+
+async fn is_ignored(
+    cx: &Context,
+    guild_id: GuildId,
+    ignores: &[MessageLogIgnore],
+    msg: &Message,
+) -> bool {
+    if ignores.is_empty() {
+        return false;
+    }
+    let has_kind = |kind| ignores.iter().any(|x: &MessageLogIgnore| x.kind == kind);
+    if msg.webhook_id.is_some() {
+        if has_kind(MessageLogIgnoreKind::Webhooks) {
+            return true;
+        }
+    } else if msg.author.bot && has_kind(MessageLogIgnoreKind::Bots) {
+        return true;
+    }
+    if ignores
+        .iter()
+        .any(|x| x.matches(MessageLogIgnoreKind::User, msg.author.id.get()))
+    {
+        return true;
+    }
+    if is_channel_ignored(cx, ignores, msg.channel_id).await {
+        return true;
+    }
+    if !has_kind(MessageLogIgnoreKind::Role) || msg.webhook_id.is_some() {
+        return false;
+    }
+    let roles = match &msg.member {
+        Some(member) => member.roles.clone(),
+        // messages from the store don't have the member, the author may have left too.
+        None => guild_id
+            .member(cx, msg.author.id)
+            .await
+            .map(|x| x.roles)
+            .unwrap_or_default(),
+    };
+    roles.iter().any(|role| {
+        ignores
+            .iter()
+            .any(|x| x.matches(MessageLogIgnoreKind::Role, role.get()))
+    })
+}
 
 pub async fn handle_message_delete(
     cx: Context,
@@ -14,21 +362,49 @@ pub async fn handle_message_delete(
     let Some(guild_id) = guild_id else {
         return;
     };
-    let Some(cached_msg) = cx
-        .cache
-        .message(channel_id, deleted_message_id)
-        .map(|x| x.clone())
+    let pool = get_pool_from_serenity(&cx).await;
+    let Some(cached_msg) = find_deleted_message(&cx, &pool, channel_id, deleted_message_id).await
     else {
         // we missed out the message...
         return;
     };
-    if let Some(log_channel) = GuildSettings::get(
-        &get_pool_from_serenity(&cx).await,
-        guild_id,
-        "message_change_log_channel",
-    )
-    .map(|x| ChannelId::new(x.parse().unwrap()))
+    let log_channel = if is_ignored(&cx, guild_id, &get_ignores(&pool, guild_id), &cached_msg).await
     {
+        None
+    } else {
+        GuildSettings::get(&pool, guild_id, "message_change_log_channel")
+            .map(|x| ChannelId::new(x.parse().unwrap()))
+    };
+    let ghost_ping = ghost_ping::is_candidate(&pool, guild_id, &cached_msg);
+    if log_channel.is_none() && !ghost_ping {
+        return;
+    }
+    let deleter = find_deleter(&cx, guild_id, cached_msg.channel_id, cached_msg.author.id).await;
+    if ghost_ping {
+        match &deleter {
+            Ok(deleter) => {
+                tokio::spawn(ghost_ping::handle_message_delete(
+                    cx.clone(),
+                    guild_id,
+                    cached_msg.clone(),
+                    *deleter,
+                ));
+            }
+            // without knowing who deleted it, it may be automod or a moderator.
+            Err(_) => log::warn!("Skipped ghost ping check, the deleter is unknown."),
+        }
+    }
+    if let Some(log_channel) = log_channel {
+        let archived_attachments =
+            fetch_attachments(guild_id, cached_msg.id, &cached_msg.attachments).await;
+        let deleter = match deleter {
+            Ok(Some(moderator)) => format!("<@{}>", moderator),
+            Ok(None) => "The author".to_string(),
+            Err(err) => {
+                log::warn!("Unable to find who deleted the message: {err}");
+                "_Unknown_".to_string()
+            }
+        };
         let mut footer = vec![format!("ID: {}", cached_msg.id)];
         let mut embed = CreateEmbed::new()
             .color(Colour::RED)
@@ -36,26 +412,29 @@ pub async fn handle_message_delete(
             .fields([
                 ("User", format!("<@{}>", cached_msg.author.id), true),
                 ("Channel", format!("<#{}>", cached_msg.channel_id), true),
+                ("Deleted by", deleter, true),
                 (
                     "Sent at",
                     format!("<t:{}>", cached_msg.timestamp.timestamp()),
                     false,
                 ),
             ])
-            .author(cached_msg.author.into())
-            .description(cached_msg.content);
+            .author(cached_msg.author.into());
         if !cached_msg.attachments.is_empty() {
             embed = embed.field(
                 "Attachments",
-                cached_msg
-                    .attachments
-                    .iter()
-                    .map(|x| x.url.clone())
-                    .collect::<Vec<String>>()
-                    .join("\n"),
+                render_url_field(
+                    cached_msg
+                        .attachments
+                        .iter()
+                        .map(|x| x.url.clone())
+                        .collect(),
+                ),
                 false,
             );
-            footer.push("Attachments may already deleted by Discord.".to_string());
+            if archived_attachments.len() < cached_msg.attachments.len() {
+                footer.push("Attachments may already deleted by Discord.".to_string());
+            }
         }
         if !cached_msg.sticker_items.is_empty() {
             embed = embed.field(
@@ -76,7 +455,22 @@ pub async fn handle_message_delete(
             )
         }
         embed = embed.footer(CreateEmbedFooter::new(footer.join(" • ")));
-        tokio::spawn(log_channel.send_message(cx.clone(), CreateMessage::new().embed(embed)));
+        let messages = create_log_messages(
+            embed,
+            Colour::RED,
+            tokenize(&cached_msg.content)
+                .into_iter()
+                .map(|x| x.to_string())
+                .collect(),
+            archived_attachments,
+            || {
+                CreateAttachment::bytes(
+                    cached_msg.content.as_str(),
+                    format!("message-{}.txt", cached_msg.id),
+                )
+            },
+        );
+        send_log_messages(&cx, log_channel, messages).await;
     }
 }
 
@@ -86,10 +480,102 @@ pub async fn handle_message_delete_bulk(
     multiple_deleted_messages_ids: Vec<MessageId>,
     guild_id: Option<GuildId>,
 ) {
-    for msg in multiple_deleted_messages_ids {
-        tokio::spawn(handle_message_delete(cx.clone(), channel_id, msg, guild_id))
-            .await
-            .unwrap();
+    let Some(guild_id) = guild_id else {
+        return;
+    };
+    let pool = get_pool_from_serenity(&cx).await;
+    let Some(log_channel) = GuildSettings::get(&pool, guild_id, "message_change_log_channel")
+        .map(|x| ChannelId::new(x.parse().unwrap()))
+    else {
+        return;
+    };
+    let ignores = get_ignores(&pool, guild_id);
+    if is_channel_ignored(&cx, &ignores, channel_id).await {
+        return;
+    }
+    // bulk deletions are made by moderators or bots, they are never checked for ghost pings.
+    let mut found = vec![];
+    let mut missing = vec![];
+    for id in &multiple_deleted_messages_ids {
+        match cx.cache.message(channel_id, *id).map(|x| x.clone()) {
+            Some(msg) => found.push(msg),
+            None => missing.push(*id),
+        }
+    }
+    // the cache doesn't have them, try the message store in one go.
+    if !missing.is_empty() {
+        found.extend(message_store::fetch_messages(&cx, &pool, &missing).await);
+    }
+    let mut messages = vec![];
+    let mut ignored = 0;
+    for msg in found {
+        if is_ignored(&cx, guild_id, &ignores, &msg).await {
+            ignored += 1;
+        } else {
+            messages.push(msg);
+        }
+    }
+    if ignored == multiple_deleted_messages_ids.len() {
+        return;
+    }
+    messages.sort_by_key(|x| x.id);
+    let mut authors: Vec<UserId> = messages.iter().map(|x| x.author.id).collect();
+    authors.sort();
+    authors.dedup();
+    let mut authors_field = authors
+        .iter()
+        .take(BULK_DELETE_MAX_AUTHORS)
+        .map(|x| format!("<@{}>", x))
+        .collect::<Vec<String>>()
+        .join(" ");
+    if authors.len() > BULK_DELETE_MAX_AUTHORS {
+        authors_field.push_str(&format!(
+            " and {} more",
+            authors.len() - BULK_DELETE_MAX_AUTHORS
+        ));
+    }
+    let mut embed = CreateEmbed::new()
+        .color(Colour::RED)
+        .title("Messages Bulk Deleted")
+        .fields([
+            (
+                "Count",
+                (multiple_deleted_messages_ids.len() - ignored).to_string(),
+                true,
+            ),
+            ("Channel", format!("<#{}>", channel_id), true),
+            (
+                "Authors",
+                if authors_field.is_empty() {
+                    "_Unknown_".to_string()
+                } else {
+                    authors_field
+                },
+                false,
+            ),
+        ]);
+    let missing = multiple_deleted_messages_ids.len() - ignored - messages.len();
+    if missing > 0 {
+        embed = embed.footer(CreateEmbedFooter::new(format!(
+            "{} message(s) were not recovered and are missing from the transcript.",
+            missing
+        )));
+    }
+    let mut message = CreateMessage::new().embed(embed);
+    if !messages.is_empty() {
+        let channel_name = channel_id.name(&cx).await.unwrap_or(channel_id.to_string());
+        message = message.add_files(create_transcript_attachments(
+            format!("Deleted messages in #{}", channel_name),
+            format!(
+                "transcript-{}-{}",
+                channel_id,
+                chrono::Utc::now().timestamp()
+            ),
+            &messages,
+        ));
+    }
+    if let Err(err) = log_channel.send_message(&cx, message).await {
+        log::warn!("Unable to send bulk delete log: {err:?}");
     }
 }
 
@@ -115,31 +601,47 @@ pub async fn handle_message_update(
     new: Option<Message>,
     event: MessageUpdateEvent,
 ) {
+    let Some(guild_id) = event.guild_id else {
+        return;
+    };
+    let pool = get_pool_from_serenity(&cx).await;
+    let old_if_available = match old_if_available {
+        Some(msg) => Some(msg),
+        None => message_store::fetch_message(&cx, &pool, event.id).await,
+    };
     let Some(old_message) = old_if_available else {
         // still, we missed the message.
         return;
     };
-    let Some(msg) = new else {
-        // where is the message?
-        return;
+    let msg = match new {
+        Some(msg) => msg,
+        None => {
+            // the cache doesn't have the message, rebuild it from the stored one.
+            let mut msg = old_message.clone();
+            event.apply_to_message(&mut msg);
+            msg
+        }
     };
+    if let Err(err) = message_store::update_message(&pool, guild_id, &msg) {
+        log::warn!("Unable to update stored message: {err}");
+    }
+    tokio::spawn(ghost_ping::handle_message_update(
+        cx.clone(),
+        guild_id,
+        old_message.clone(),
+        msg.clone(),
+    ));
+    if is_ignored(&cx, guild_id, &get_ignores(&pool, guild_id), &msg).await {
+        return;
+    }
     let Some(edited_timestamp) = msg.edited_timestamp else {
         // editing embed doesn't add an edited timestamp (probably)
         return;
     };
-    let Some(guild_id) = event.guild_id else {
-        return;
-    };
-    if let Some(log_channel) = GuildSettings::get(
-        &get_pool_from_serenity(&cx).await,
-        guild_id,
-        "message_change_log_channel",
-    )
-    .map(|x| ChannelId::new(x.parse().unwrap()))
+    if let Some(log_channel) = GuildSettings::get(&pool, guild_id, "message_change_log_channel")
+        .map(|x| ChannelId::new(x.parse().unwrap()))
     {
-        let author = event
-            .author
-            .expect("Why an edited message doesn't have an author.");
+        let author = msg.author.clone();
         let removed_attachments =
             find_attachments_diff(old_message.attachments, msg.attachments, |a, b| {
                 a.id == b.id
@@ -148,6 +650,7 @@ pub async fn handle_message_update(
             find_attachments_diff(old_message.sticker_items, msg.sticker_items, |a, b| {
                 a.id == b.id
             });
+        let archived_attachments = fetch_attachments(guild_id, msg.id, &removed_attachments).await;
         let mut footer = vec![format!("ID: {}", msg.id)];
         let mut embed = CreateEmbed::new()
             .color(Colour::ORANGE)
@@ -165,29 +668,16 @@ pub async fn handle_message_update(
                     false,
                 ),
             ])
-            .author(author.into())
-            .description(if old_message.content != msg.content {
-                format!(
-                    "**_Old Message_**\n\
-                    {}\n\n\
-                    **_New Message_**\n\
-                    {}",
-                    old_message.content, msg.content
-                )
-            } else {
-                "_No content changes._".to_string()
-            });
+            .author(author.into());
         if !removed_attachments.is_empty() {
             embed = embed.field(
                 "Removed Attachments",
-                removed_attachments
-                    .iter()
-                    .map(|x| x.url.clone())
-                    .collect::<Vec<String>>()
-                    .join("\n"),
+                render_url_field(removed_attachments.iter().map(|x| x.url.clone()).collect()),
                 false,
             );
-            footer.push("Attachments may already deleted by Discord.".to_string());
+            if archived_attachments.len() < removed_attachments.len() {
+                footer.push("Attachments may already deleted by Discord.".to_string());
+            }
         }
         if !removed_stickers.is_empty() {
             embed = embed.field(
@@ -207,9 +697,21 @@ pub async fn handle_message_update(
             )
         }
         embed = embed.footer(CreateEmbedFooter::new(footer.join(" • ")));
-        log_channel
-            .send_message(cx.clone(), CreateMessage::new().embed(embed))
-            .await
-            .expect("Cannot send message update log.");
+        let content = if old_message.content != msg.content {
+            render_word_diff(&old_message.content, &msg.content)
+        } else {
+            vec!["_No content changes._".to_string()]
+        };
+        let messages =
+            create_log_messages(embed, Colour::ORANGE, content, archived_attachments, || {
+                CreateAttachment::bytes(
+                    format!(
+                        "Old Message\n{}\n\nNew Message\n{}\n",
+                        old_message.content, msg.content
+                    ),
+                    format!("message-{}.txt", msg.id),
+                )
+            });
+        send_log_messages(&cx, log_channel, messages).await;
     }
 }
@@ -0,0 +1,203 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use chrono::TimeDelta;
+use diesel::{
+    delete, insert_into, update, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
+use fang::typetag;
+use fang::AsyncQueueable;
+use fang::AsyncRunnable;
+use fang::FangError;
+use fang::Scheduled;
+use serde::{Deserialize, Serialize};
+use serenity::all::{Context, GuildId, Message, MessageId, User, UserId};
+use serenity::async_trait;
+
+use crate::{
+    acquire_pool,
+    models::{
+        guild_settings::GuildSettings,
+        stored_message::{CreateStoredMessage, StoredMessage},
+    },
+    schema::stored_messages,
+    util::get_pool_from_serenity,
+    ConnectionPool, Error,
+};
+
+use super::attachment_archive::prune_attachments;
+
+pub const RETENTION_SETTING: &str = "message_store_retention";
+
+pub fn is_enabled<G: Into<GuildId>>(pool: &ConnectionPool, guild: G) -> bool {
+    GuildSettings::get(pool, guild, RETENTION_SETTING).is_some()
+}
+
+pub async fn handle_message(cx: Context, msg: Message) {
+    let Some(guild_id) = msg.guild_id else {
+        return;
+    };
+    let pool = get_pool_from_serenity(&cx).await;
+    if !is_enabled(&pool, guild_id) {
+        return;
+    }
+    let Ok(mut conn) = pool.get() else {
+        return;
+    };
+    if let Err(err) = insert_into(stored_messages::table)
+        .values(CreateStoredMessage::new(guild_id, &msg))
+        .on_conflict_do_nothing()
+        .execute(&mut conn)
+    {
+        log::warn!("Unable to store message: {err}");
+    }
+}
+
+/// Update the stored copy of a message after it has been edited, nothing is stored for guilds
+/// that haven't opted in.
+pub fn update_message<G: Into<GuildId>>(
+    pool: &ConnectionPool,
+    guild: G,
+    msg: &Message,
+) -> Result<(), Error> {
+    let guild = guild.into();
+    if !is_enabled(pool, guild) {
+        return Ok(());
+    }
+    update(stored_messages::table)
+        .filter(stored_messages::guild.eq(TryInto::<i64>::try_into(guild.get()).unwrap()))
+        .filter(stored_messages::id.eq(TryInto::<i64>::try_into(msg.id.get()).unwrap()))
+        .set((
+            stored_messages::content.eq(&msg.content),
+            stored_messages::attachments.eq(serde_json::to_value(&msg.attachments)?),
+            stored_messages::edited_at.eq(msg.edited_timestamp.map(|x| x.naive_utc())),
+        ))
+        .execute(&mut pool.get()?)?;
+    Ok(())
+}
+
+/// Rebuild a message from the store, used when serenity's cache doesn't have it.
+pub async fn fetch_message(cx: &Context, pool: &ConnectionPool, id: MessageId) -> Option<Message> {
+    let stored: StoredMessage = stored_messages::table
+        .find(TryInto::<i64>::try_into(id.get()).unwrap())
+        .select(StoredMessage::as_select())
+        .get_result(&mut pool.get().ok()?)
+        .optional()
+        .ok()??;
+    let author = get_author(cx, UserId::new(stored.author.try_into().unwrap())).await?;
+    Some(stored.into_message(author))
+}
+
+/// Rebuild several messages from the store at once, messages that aren't stored are skipped.
+pub async fn fetch_messages(
+    cx: &Context,
+    pool: &ConnectionPool,
+    ids: &[MessageId],
+) -> Vec<Message> {
+    let Ok(mut conn) = pool.get() else {
+        return vec![];
+    };
+    let stored: Vec<StoredMessage> = match stored_messages::table
+        .filter(
+            stored_messages::id.eq_any(
+                ids.iter()
+                    .map(|x| TryInto::<i64>::try_into(x.get()).unwrap())
+                    .collect::<Vec<i64>>(),
+            ),
+        )
+        .select(StoredMessage::as_select())
+        .load(&mut conn)
+    {
+        Ok(x) => x,
+        Err(err) => {
+            log::warn!("Unable to fetch stored messages: {err}");
+            return vec![];
+        }
+    };
+    // authors are resolved once, a purge usually has many messages of the same authors.
+    let mut authors: HashMap<UserId, Option<User>> = HashMap::new();
+    for stored in &stored {
+        let id = UserId::new(stored.author.try_into().unwrap());
+        if let Entry::Vacant(entry) = authors.entry(id) {
+            entry.insert(get_author(cx, id).await);
+        }
+    }
+    stored
+        .into_iter()
+        .filter_map(|x| {
+            let author = authors[&UserId::new(x.author.try_into().unwrap())].clone()?;
+            Some(x.into_message(author))
+        })
+        .collect()
+}
+
+/// Get the author of a stored message, from the cache if possible.
+pub async fn get_author(cx: &Context, id: UserId) -> Option<User> {
+    let cached = cx.cache.user(id).map(|x| x.clone());
+    match cached {
+        Some(user) => Some(user),
+        None => id.to_user(cx).await.ok(),
+    }
+}
+
+fn prune_messages(pool: &ConnectionPool) -> Result<usize, Error> {
+    let mut conn = pool.get()?;
+    let mut count = 0;
+    let mut guilds = vec![];
+    for (guild, retention) in GuildSettings::all(pool, RETENTION_SETTING)? {
+        let Ok(retention) = retention.parse::<i64>() else {
+            continue;
+        };
+        let guild: i64 = guild.get().try_into().unwrap();
+        guilds.push(guild);
+        count += delete(stored_messages::table)
+            .filter(stored_messages::guild.eq(guild))
+            .filter(
+                stored_messages::created_at
+                    .lt(chrono::Utc::now().naive_utc() - TimeDelta::seconds(retention)),
+            )
+            .execute(&mut conn)?;
+    }
+    // guilds that have disabled the message store shouldn't keep anything.
+    count += delete(stored_messages::table)
+        .filter(stored_messages::guild.ne_all(guilds))
+        .execute(&mut conn)?;
+    Ok(count)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(crate = "fang::serde")]
+pub struct PruneStoredMessages {}
+
+#[typetag::serde]
+#[async_trait]
+impl AsyncRunnable for PruneStoredMessages {
+    async fn run(&self, _queue: &dyn AsyncQueueable) -> Result<(), FangError> {
+        let pool = acquire_pool();
+        let count = prune_messages(&pool).map_err(|x| FangError {
+            description: x.to_string(),
+        })?;
+        if count > 0 {
+            log::info!("Pruned {} stored messages.", count);
+        }
+        let count = prune_attachments(&pool).await.map_err(|x| FangError {
+            description: x.to_string(),
+        })?;
+        if count > 0 {
+            log::info!("Pruned {} archived attachments.", count);
+        }
+        Ok(())
+    }
+
+    fn uniq(&self) -> bool {
+        true
+    }
+
+    fn cron(&self) -> Option<Scheduled> {
+        Some(Scheduled::CronPattern("0 0 * * * *".to_string()))
+    }
+
+    fn max_retries(&self) -> i32 {
+        3
+    }
+}
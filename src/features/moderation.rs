@@ -1,10 +1,12 @@
+use std::collections::HashMap;
+
 use diesel::RunQueryDsl;
 use fang::{AsyncQueue, AsyncQueueable};
 use poise::CreateReply;
 use serenity::all::{
     ActionRowComponent, CacheHttp, ChannelId, Colour, Context, CreateEmbed,
-    CreateInteractionResponse, CreateInteractionResponseMessage, Interaction, Member, RoleId, User,
-    UserId,
+    CreateInteractionResponse, CreateInteractionResponseMessage, EditMember, Interaction, Member,
+    RoleId, Timestamp, User, UserId,
 };
 
 use crate::{
@@ -14,7 +16,7 @@ use crate::{
         moderation_log::{CreateModerationLog, ModerationAction, ModerationLog},
     },
     util::{
-        get_pool_from_serenity, parse_duration_to_seconds,
+        expiry_from_now, get_pool_from_serenity, parse_duration_to_seconds,
         send_moderation_logs_with_database_records,
     },
     ConnectionPool, Error,
@@ -32,11 +34,13 @@ pub async fn inspect_impl<F>(
 where
     F: AsRef<str>,
 {
+    let mut blocks = 0;
     let mut warns = 0;
     let mut floods = 0;
     let mut timeouts = 0;
     let mut softbans = 0;
     let mut bans = 0;
+    let active: HashMap<ModerationAction, i64>;
     let logs: Vec<CreateEmbed> = {
         use diesel::dsl::*;
         use diesel::ExpressionMethods;
@@ -51,6 +55,7 @@ where
                 .load::<(ModerationAction, i64)>(&mut conn)?
         } {
             match result.0 {
+                ModerationAction::Block => blocks = result.1,
                 ModerationAction::Warning => warns = result.1,
                 ModerationAction::Flood => floods = result.1,
                 ModerationAction::Timeout => timeouts = result.1,
@@ -58,11 +63,26 @@ where
                 ModerationAction::Ban => bans = result.1,
             }
         }
+        active = {
+            use crate::schema::moderation_log::*;
+            table
+                .filter(ModerationLog::by_user(user.clone()))
+                .filter(ModerationLog::active())
+                .group_by(kind)
+                .select((kind, count_star()))
+                .load::<(ModerationAction, i64)>(&mut conn)?
+                .into_iter()
+                .collect()
+        };
         let mut query = ModerationLog::all().filter(ModerationLog::by_user(user.clone()));
         if let Some(filter) = filter {
             for str in filter.as_ref().split(",") {
-                let Ok(action): Result<ModerationAction, _> = str.trim().to_lowercase().try_into()
-                else {
+                let str = str.trim().to_lowercase();
+                if str == "active" {
+                    query = query.filter(ModerationLog::active());
+                    continue;
+                }
+                let Ok(action): Result<ModerationAction, _> = str.clone().try_into() else {
                     return Ok(CreateReply {
                         content: Some(format!("Unknown filter: {}", str)),
                         ..Default::default()
@@ -79,6 +99,17 @@ where
             .map(|x| x.into())
             .collect()
     };
+    let summary = |kind: ModerationAction, count: i64| {
+        (
+            kind.embed_title().to_string(),
+            format!(
+                "{} time(s), {} active",
+                count,
+                active.get(&kind).unwrap_or(&0)
+            ),
+            true,
+        )
+    };
     Ok(CreateReply {
         content: Some(format!("Moderation logs for <@{}>", user.id.get())),
         embeds: [
@@ -86,31 +117,12 @@ where
                 .title("Summary of moderations")
                 .color(Colour::BLUE)
                 .fields([
-                    (
-                        ModerationAction::Warning.embed_title(),
-                        format!("{} time(s)", warns),
-                        true,
-                    ),
-                    (
-                        ModerationAction::Flood.embed_title(),
-                        format!("{} time(s)", floods),
-                        true,
-                    ),
-                    (
-                        ModerationAction::Timeout.embed_title(),
-                        format!("{} time(s)", timeouts),
-                        true,
-                    ),
-                    (
-                        ModerationAction::Softban.embed_title(),
-                        format!("{} time(s)", softbans),
-                        true,
-                    ),
-                    (
-                        ModerationAction::Ban.embed_title(),
-                        format!("{} time(s)", bans),
-                        true,
-                    ),
+                    summary(ModerationAction::Warning, warns),
+                    summary(ModerationAction::Flood, floods),
+                    summary(ModerationAction::Timeout, timeouts),
+                    summary(ModerationAction::Softban, softbans),
+                    summary(ModerationAction::Ban, bans),
+                    summary(ModerationAction::Block, blocks),
                 ])],
             logs,
         ]
@@ -128,6 +140,24 @@ pub async fn warning_impl<T: CacheHttp>(
     reason: Option<String>,
     duration: Option<String>,
 ) -> Result<String, Error> {
+    let duration = match duration {
+        Some(mut duration) => {
+            let duration_secs = match parse_duration_to_seconds(&duration) {
+                Ok(x) => x,
+                Err(err) => {
+                    return Ok(err);
+                }
+            };
+            if duration_secs == 0 {
+                return Ok("Invalid duration".to_string());
+            }
+            if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+                duration.push('s');
+            }
+            Some((duration, duration_secs))
+        }
+        None => None,
+    };
     let guild_id = member.guild_id;
     let log: ModerationLog = ModerationLog::insert()
         .values([CreateModerationLog::new(
@@ -136,7 +166,8 @@ pub async fn warning_impl<T: CacheHttp>(
             member.user.id,
             Some(actor.id),
             reason.clone(),
-        )])
+        )
+        .expires_at(duration.as_ref().map(|(_, secs)| expiry_from_now(*secs)))])
         .get_result(&mut state.0.get()?)?;
     let uuid = log.id;
     member
@@ -153,19 +184,7 @@ pub async fn warning_impl<T: CacheHttp>(
         )
         .await?;
     }
-    if let Some(mut duration) = duration {
-        let duration_secs = match parse_duration_to_seconds(&duration) {
-            Ok(x) => x,
-            Err(err) => {
-                return Ok(err);
-            }
-        };
-        if duration_secs == 0 {
-            return Ok("Invalid duration".to_string());
-        }
-        if duration.chars().last().is_some_and(|c| c.is_numeric()) {
-            duration.push('s');
-        }
+    if let Some((duration, duration_secs)) = duration {
         let task = RemoveWarning::new(uuid, duration_secs);
         state.1.schedule_task(&task).await?;
         return Ok(format!(
@@ -229,7 +248,8 @@ pub async fn flood_impl<T: CacheHttp>(
             member.user.id,
             Some(actor.id),
             reason.clone(),
-        )])
+        )
+        .expires_at(Some(expiry_from_now(duration_secs)))])
         .get_result(&mut state.0.get()?)?;
     let uuid = log.id;
     member
@@ -254,6 +274,44 @@ pub async fn flood_impl<T: CacheHttp>(
     ))
 }
 
+/// Discord doesn't allow timeouts longer than 28 days.
+pub const MAX_TIMEOUT_SECS: u64 = 28 * 86400;
+
+/// Time out the member, the case is recorded from the audit log entry of the timeout.
+pub async fn timeout_impl<T: CacheHttp>(
+    cx: &T,
+    member: Member,
+    mut duration: String,
+    reason: Option<String>,
+) -> Result<String, Error> {
+    // the callers are automated, invalid durations are errors so they don't go unnoticed.
+    let duration_secs = parse_duration_to_seconds(&duration)?;
+    if duration_secs == 0 {
+        return Err("Invalid duration".into());
+    }
+    if duration_secs > MAX_TIMEOUT_SECS {
+        return Err("Timeouts can't be longer than 28 days.".into());
+    }
+    if duration.chars().last().is_some_and(|c| c.is_numeric()) {
+        duration.push('s');
+    }
+    let until = Timestamp::from_unix_timestamp(
+        chrono::Utc::now().timestamp() + TryInto::<i64>::try_into(duration_secs).unwrap(),
+    )?;
+    let mut builder = EditMember::new().disable_communication_until_datetime(until);
+    if let Some(reason) = &reason {
+        builder = builder.audit_log_reason(reason);
+    }
+    member
+        .guild_id
+        .edit_member(cx, member.user.id, builder)
+        .await?;
+    Ok(format!(
+        "<@{}> has been timed out for **{}**.",
+        member.user.id, duration
+    ))
+}
+
 pub async fn softban_impl<T: CacheHttp>(
     cx: &T,
     pool: &ConnectionPool,
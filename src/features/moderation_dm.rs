@@ -17,10 +17,18 @@ pub fn generate_dm_message<T: Into<ChannelId>>(
     if let Some(channel) = channel {
         embed = embed.field("Channel", format!("<#{}>", channel.into().get()), true);
     }
+    if let Some(expires_at) = log.expires_at {
+        embed = embed.field(
+            "Expires",
+            format!("<t:{}:R>", expires_at.and_utc().timestamp()),
+            true,
+        );
+    }
     CreateMessage::new()
         .content(format!(
             "You are {} by a moderator from AIHASTO.",
             match log.kind {
+                ModerationAction::Block => "blocked",
                 ModerationAction::Warning => "warned",
                 ModerationAction::Flood => "marked as Flooder",
                 ModerationAction::Timeout => "timedout",
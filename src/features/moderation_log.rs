@@ -1,7 +1,8 @@
 use diesel::RunQueryDsl;
 use fang::AsyncQueueable;
 use serenity::all::{
-    audit_log::Action, AuditLogEntry, Change, ChannelId, Context, GuildId, MemberAction, UserId,
+    audit_log::Action, automod::Action as AutoModAction, ActionExecution, AuditLogEntry, Change,
+    ChannelId, Context, GuildId, MemberAction, UserId,
 };
 
 use crate::{
@@ -10,7 +11,7 @@ use crate::{
         guild_settings::GuildSettings,
         moderation_log::{CreateModerationLog, ModerationAction, ModerationLog},
     },
-    util::{get_pool_from_serenity, send_moderation_logs_with_database_records},
+    util::{expiry_from_now, get_pool_from_serenity, send_moderation_logs_with_database_records},
 };
 
 use super::{moderation_dm::generate_dm_message, temp_role::RemoveTempRole};
@@ -38,7 +39,7 @@ pub async fn guild_audit_log_entry_create(cx: Context, entry: AuditLogEntry, gui
             for change in changes {
                 if let Change::CommunicationDisabledUntil {
                     old: _,
-                    new: Some(_timestamp),
+                    new: Some(timestamp),
                 } = change
                 {
                     let cx = cx.clone();
@@ -52,7 +53,8 @@ pub async fn guild_audit_log_entry_create(cx: Context, entry: AuditLogEntry, gui
                                 entry.target_id.unwrap().get(),
                                 Some(entry.user_id),
                                 reason,
-                            )])
+                            )
+                            .expires_at(Some(timestamp.naive_utc()))])
                             .get_results(&mut pool.get().unwrap())
                             .expect("Unable to log timeout.");
                         let target = UserId::new(entry.target_id.unwrap().get());
@@ -103,3 +105,54 @@ pub async fn guild_audit_log_entry_create(cx: Context, entry: AuditLogEntry, gui
         _ => {}
     }
 }
+
+/// Record Discord AutoMod blocks and timeouts as cases, AutoMod has no moderator.
+pub async fn handle_auto_moderation_action(cx: Context, execution: ActionExecution) {
+    let (kind, expires_at) = match execution.action {
+        AutoModAction::BlockMessage { .. } => (ModerationAction::Block, None),
+        AutoModAction::Timeout(duration) => (
+            ModerationAction::Timeout,
+            Some(expiry_from_now(duration.as_secs())),
+        ),
+        _ => return,
+    };
+    let guild_id = execution.guild_id;
+    let rule = match guild_id.automod_rule(&cx, execution.rule_id).await {
+        Ok(rule) => format!("\"{}\"", rule.name),
+        Err(_) => format!("`{}`", execution.rule_id),
+    };
+    let reason = match execution.matched_keyword.or(execution.matched_content) {
+        Some(keyword) => format!(
+            "AutoMod rule {} matched `{}`",
+            rule,
+            keyword.replace('`', "'")
+        ),
+        None => format!("AutoMod rule {} triggered", rule),
+    };
+    let pool = get_pool_from_serenity(&cx).await;
+    let logs = match ModerationLog::insert()
+        .values([CreateModerationLog::new(
+            guild_id,
+            kind,
+            execution.user_id,
+            None::<UserId>,
+            Some(reason),
+        )
+        .expires_at(expires_at)])
+        .get_results(&mut pool.get().unwrap())
+    {
+        Ok(logs) => logs,
+        Err(err) => {
+            log::error!("Unable to log AutoMod action: {err}");
+            return;
+        }
+    };
+    if let Some(channel) = GuildSettings::get(&pool, guild_id, "moderation_log_channel") {
+        let channel = ChannelId::new(channel.parse().unwrap());
+        if let Err(err) =
+            send_moderation_logs_with_database_records(&pool, &cx, guild_id, channel, logs).await
+        {
+            log::warn!("Unable to send AutoMod moderation logs: {err}");
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::{
+    collections::HashSet,
+    env, fs,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use dashmap::DashMap;
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serenity::all::{Context, GuildId, Message, User};
+
+use crate::{
+    data::QueueKey, models::guild_settings::GuildSettings, schema::phishing_domains,
+    util::get_pool_from_serenity, ConnectionPool, Error,
+};
+
+use super::moderation::{flood_impl, softban_impl, timeout_impl};
+
+pub const ENABLED_SETTING: &str = "phishing_filter";
+pub const ACTION_SETTING: &str = "phishing_action";
+pub const DURATION_SETTING: &str = "phishing_action_duration";
+
+const DEFAULT_DURATION: &str = "1d";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum PhishingAction {
+    #[name = "delete"]
+    Delete,
+    #[name = "timeout"]
+    Timeout,
+    #[name = "flood"]
+    Flood,
+    #[name = "softban"]
+    Softban,
+}
+
+impl PhishingAction {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Delete => "delete",
+            Self::Timeout => "timeout",
+            Self::Flood => "flood",
+            Self::Softban => "softban",
+        }
+    }
+
+    fn from_setting<G: Into<GuildId>>(pool: &ConnectionPool, guild: G) -> Self {
+        match GuildSettings::get(pool, guild, ACTION_SETTING).as_deref() {
+            Some("delete") => Self::Delete,
+            Some("timeout") => Self::Timeout,
+            Some("flood") => Self::Flood,
+            _ => Self::Softban,
+        }
+    }
+}
+
+lazy_static! {
+    static ref DOMAIN_REGEX: Regex =
+        Regex::new(r"(?i)\b((?:[a-z0-9](?:[a-z0-9-]*[a-z0-9])?\.)+[a-z]{2,63})\b").unwrap();
+    /// Domains loaded from `PHISHING_DOMAINS_PATH`.
+    static ref DOMAINS: RwLock<Arc<HashSet<String>>> = RwLock::new(Arc::new(HashSet::new()));
+    static ref GUILD_DOMAINS: DashMap<GuildId, Arc<HashSet<String>>> = DashMap::new();
+}
+
+/// Read the domains of a list, one per line. Hosts files (`0.0.0.0 example.com`) are supported
+/// and lines starting with `#` are ignored.
+fn parse_domains(content: &str, domains: &mut HashSet<String>) {
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(domain) = line.split_whitespace().last() {
+            domains.insert(normalize_domain(domain));
+        }
+    }
+}
+
+pub fn normalize_domain(domain: &str) -> String {
+    domain
+        .trim()
+        .trim_start_matches("*.")
+        .trim_end_matches('.')
+        .to_lowercase()
+}
+
+/// (Re)load the domain list from `PHISHING_DOMAINS_PATH`, which is a file or a directory of files.
+/// The current list is kept if it can't be read.
+pub fn reload_domains() -> Result<usize, Error> {
+    let Ok(path) = env::var("PHISHING_DOMAINS_PATH") else {
+        return Ok(0);
+    };
+    let path = Path::new(&path);
+    let mut domains = HashSet::new();
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?.path();
+            if entry.is_file() {
+                parse_domains(&fs::read_to_string(entry)?, &mut domains);
+            }
+        }
+    } else {
+        parse_domains(&fs::read_to_string(path)?, &mut domains);
+    }
+    let count = domains.len();
+    *DOMAINS.write().unwrap() = Arc::new(domains);
+    Ok(count)
+}
+
+pub fn get_guild_domains<G: Into<GuildId>>(
+    pool: &ConnectionPool,
+    guild: G,
+) -> Result<Arc<HashSet<String>>, Error> {
+    let guild = guild.into();
+    if let Some(domains) = GUILD_DOMAINS.get(&guild) {
+        return Ok(domains.clone());
+    }
+    let domains: Vec<String> = phishing_domains::table
+        .filter(phishing_domains::guild.eq(TryInto::<i64>::try_into(guild.get()).unwrap()))
+        .select(phishing_domains::domain)
+        .load(&mut pool.get()?)?;
+    let domains = Arc::new(domains.into_iter().collect::<HashSet<String>>());
+    GUILD_DOMAINS.insert(guild, domains.clone());
+    Ok(domains)
+}
+
+/// Forget the cached domains of the guild after they have been changed.
+pub fn invalidate_guild_domains<G: Into<GuildId>>(guild: G) {
+    GUILD_DOMAINS.remove(&guild.into());
+}
+
+/// Find a listed domain in the content, subdomains of listed domains match too.
+fn find_domain(content: &str, lists: &[&HashSet<String>]) -> Option<String> {
+    for capture in DOMAIN_REGEX.captures_iter(content) {
+        let domain = capture[1].to_lowercase();
+        let mut suffix = domain.as_str();
+        loop {
+            if lists.iter().any(|x| x.contains(suffix)) {
+                return Some(suffix.to_string());
+            }
+            match suffix.split_once('.') {
+                Some((_, parent)) if parent.contains('.') => suffix = parent,
+                _ => break,
+            }
+        }
+    }
+    None
+}
+
+pub async fn handle_message(cx: Context, msg: Message) -> bool {
+    let Some(guild_id) = msg.guild_id else {
+        return false;
+    };
+    if msg.author.bot || msg.webhook_id.is_some() || msg.content.is_empty() {
+        return false;
+    }
+    let pool = get_pool_from_serenity(&cx).await;
+    if GuildSettings::get(&pool, guild_id, ENABLED_SETTING).is_none() {
+        return false;
+    }
+    let guild_domains = match get_guild_domains(&pool, guild_id) {
+        Ok(domains) => domains,
+        Err(err) => {
+            log::warn!("Unable to get phishing domains: {err}");
+            return false;
+        }
+    };
+    let domains = DOMAINS.read().unwrap().clone();
+    let Some(domain) = find_domain(&msg.content, &[&domains, &guild_domains]) else {
+        return false;
+    };
+    if let Err(err) = msg.delete(&cx).await {
+        log::warn!("Unable to delete phishing link: {err}");
+    }
+    let action = PhishingAction::from_setting(&pool, guild_id);
+    if action == PhishingAction::Delete {
+        return true;
+    }
+    let Ok(member) = guild_id.member(&cx, msg.author.id).await else {
+        return true;
+    };
+    let reason = Some(format!("Posting a phishing link: {}", domain));
+    let duration = GuildSettings::get(&pool, guild_id, DURATION_SETTING)
+        .unwrap_or(DEFAULT_DURATION.to_string());
+    let bot: User = cx.cache.current_user().clone().into();
+    let res = match action {
+        PhishingAction::Timeout => timeout_impl(&cx, member, duration, reason).await,
+        PhishingAction::Flood => {
+            let queue = cx.data.read().await.get::<QueueKey>().unwrap().clone();
+            flood_impl(
+                &cx,
+                (&pool, &queue),
+                msg.channel_id,
+                member,
+                &bot,
+                duration,
+                reason,
+            )
+            .await
+        }
+        _ => softban_impl(&cx, &pool, msg.channel_id, member, &bot, reason).await,
+    };
+    if let Err(err) = res {
+        log::warn!("Unable to punish phishing link: {err}");
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_domain() {
+        let mut domains = HashSet::new();
+        parse_domains(
+            "# scam list\n0.0.0.0 steamcommunlty.com\ndiscord-nitro.gift\n",
+            &mut domains,
+        );
+        let lists = [&domains];
+        assert_eq!(
+            find_domain("free https://login.steamcommunlty.com/x", &lists),
+            Some("steamcommunlty.com".to_string())
+        );
+        assert_eq!(
+            find_domain("claim DISCORD-NITRO.GIFT now", &lists),
+            Some("discord-nitro.gift".to_string())
+        );
+        assert_eq!(find_domain("see steamcommunity.com", &lists), None);
+    }
+}
@@ -0,0 +1,234 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serenity::all::{
+    CacheHttp, ChannelId, Colour, CreateEmbed, CreateEmbedFooter, CreateMessage, GetMessages,
+    GuildId, Message, MessageId, User, UserId,
+};
+
+use crate::{models::guild_settings::GuildSettings, ConnectionPool, Error};
+
+use super::transcript::create_transcript_attachments;
+
+/// Discord only bulk deletes messages younger than 14 days, leave a margin for slow requests.
+const BULK_DELETE_MAX_AGE_SECS: i64 = 14 * 86400 - 60;
+/// Stop looking for matching messages after scanning this many.
+const MAX_SCANNED_MESSAGES: usize = 2000;
+
+lazy_static! {
+    static ref LINK_REGEX: Regex = Regex::new(r"(?i)https?://\S+").unwrap();
+}
+
+#[derive(Default)]
+pub struct PurgeFilter {
+    pub user: Option<UserId>,
+    pub bots: bool,
+    /// Lowercase text the content has to contain.
+    pub contains: Option<String>,
+    pub attachments: bool,
+    pub links: bool,
+    pub before: Option<MessageId>,
+    pub after: Option<MessageId>,
+}
+
+impl PurgeFilter {
+    pub fn matches(&self, msg: &Message) -> bool {
+        if self.user.is_some_and(|x| x != msg.author.id) {
+            return false;
+        }
+        if self.bots && !msg.author.bot {
+            return false;
+        }
+        if self
+            .contains
+            .as_ref()
+            .is_some_and(|x| !msg.content.to_lowercase().contains(x))
+        {
+            return false;
+        }
+        if self.attachments && msg.attachments.is_empty() {
+            return false;
+        }
+        if self.links && !LINK_REGEX.is_match(&msg.content) {
+            return false;
+        }
+        true
+    }
+
+    fn describe(&self) -> String {
+        let mut filters = vec![];
+        if let Some(user) = self.user {
+            filters.push(format!("From <@{}>", user));
+        }
+        if self.bots {
+            filters.push("From bots".to_string());
+        }
+        if let Some(contains) = &self.contains {
+            filters.push(format!("Contains `{}`", contains.replace('`', "'")));
+        }
+        if self.attachments {
+            filters.push("Has attachments".to_string());
+        }
+        if self.links {
+            filters.push("Has links".to_string());
+        }
+        if filters.is_empty() {
+            "None".to_string()
+        } else {
+            filters.join("\n")
+        }
+    }
+}
+
+/// Find up to `count` matching messages between `after` and `before`, newest first.
+async fn find_messages<T: CacheHttp>(
+    cx: &T,
+    channel: ChannelId,
+    count: usize,
+    filter: &PurgeFilter,
+) -> Result<Vec<Message>, Error> {
+    let mut found = vec![];
+    let mut cursor = filter.before;
+    let mut scanned = 0;
+    while found.len() < count && scanned < MAX_SCANNED_MESSAGES {
+        let mut builder = GetMessages::new().limit(100);
+        if let Some(cursor) = cursor {
+            builder = builder.before(cursor);
+        }
+        let messages = channel.messages(cx, builder).await?;
+        let Some(last) = messages.last() else {
+            break;
+        };
+        cursor = Some(last.id);
+        scanned += messages.len();
+        for msg in messages {
+            if filter.after.is_some_and(|x| msg.id <= x) {
+                return Ok(found);
+            }
+            if filter.matches(&msg) {
+                found.push(msg);
+                if found.len() == count {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+async fn send_purge_log<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    channel: ChannelId,
+    embed: CreateEmbed,
+    messages: &[Message],
+) {
+    let Some(log_channel) = GuildSettings::get(pool, guild, "moderation_log_channel")
+        .map(|x| ChannelId::new(x.parse().unwrap()))
+    else {
+        return;
+    };
+    let mut messages = messages.to_vec();
+    messages.sort_by_key(|x| x.id);
+    let channel_name = channel.name(cx).await.unwrap_or(channel.to_string());
+    let message = CreateMessage::new()
+        .embed(embed)
+        .add_files(create_transcript_attachments(
+            format!("Purged messages in #{}", channel_name),
+            format!("purge-{}-{}", channel, chrono::Utc::now().timestamp()),
+            &messages,
+        ));
+    if let Err(err) = log_channel.send_message(cx, message).await {
+        log::warn!("Unable to send purge log: {err:?}");
+    }
+}
+
+/// Delete the matching messages, bulk deleting recent ones and deleting older ones one by one.
+pub async fn purge_impl<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    channel: ChannelId,
+    actor: &User,
+    count: usize,
+    filter: PurgeFilter,
+) -> Result<String, Error> {
+    let messages = find_messages(cx, channel, count, &filter).await?;
+    if messages.is_empty() {
+        return Ok("No matching messages have been found.".to_string());
+    }
+    let bulk_limit = chrono::Utc::now().timestamp() - BULK_DELETE_MAX_AGE_SECS;
+    let (recent, old): (Vec<&Message>, Vec<&Message>) = messages
+        .iter()
+        .partition(|x| x.id.created_at().unix_timestamp() > bulk_limit);
+    let mut failed = 0;
+    for chunk in recent.chunks(100) {
+        if let Err(err) = channel
+            .delete_messages(cx.http(), chunk.iter().map(|x| x.id))
+            .await
+        {
+            log::warn!("Unable to bulk delete purged messages: {err}");
+            failed += chunk.len();
+        }
+    }
+    for msg in old {
+        if let Err(err) = channel.delete_message(cx.http(), msg.id).await {
+            log::warn!("Unable to delete purged message: {err}");
+            failed += 1;
+        }
+    }
+    let mut embed = CreateEmbed::new()
+        .color(Colour::DARK_ORANGE)
+        .title("🧹 Purge")
+        .fields([
+            ("Count", (messages.len() - failed).to_string(), true),
+            ("Channel", format!("<#{}>", channel), true),
+            ("Moderator", format!("<@{}>", actor.id), true),
+            ("Filters", filter.describe(), false),
+        ]);
+    if failed > 0 {
+        embed = embed.footer(CreateEmbedFooter::new(format!(
+            "{} message(s) couldn't be deleted.",
+            failed
+        )));
+    }
+    send_purge_log(cx, pool, guild, channel, embed, &messages).await;
+    Ok(if failed == 0 {
+        format!("Deleted **{}** messages.", messages.len())
+    } else {
+        format!(
+            "Deleted **{}** messages, **{}** couldn't be deleted.",
+            messages.len() - failed,
+            failed
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_purge_filter() {
+        let mut msg = Message::default();
+        msg.author.id = UserId::new(1);
+        msg.content = "Free Nitro at https://example.com".to_string();
+        let filter = PurgeFilter {
+            user: Some(UserId::new(1)),
+            contains: Some("nitro".to_string()),
+            links: true,
+            ..Default::default()
+        };
+        assert!(filter.matches(&msg));
+        assert!(!PurgeFilter {
+            bots: true,
+            ..Default::default()
+        }
+        .matches(&msg));
+        assert!(!PurgeFilter {
+            attachments: true,
+            ..Default::default()
+        }
+        .matches(&msg));
+    }
+}
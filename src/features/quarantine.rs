@@ -0,0 +1,222 @@
+use diesel::{
+    delete, insert_into, ExpressionMethods, OptionalExtension, QueryDsl, RunQueryDsl,
+    SelectableHelper,
+};
+use fang::{async_trait, typetag, AsyncQueueable, AsyncRunnable, FangError, Scheduled};
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    CacheHttp, Colour, Context, CreateEmbed, CreateEmbedFooter, GuildId, Member, RoleId, UserId,
+};
+
+use crate::{
+    acquire_cache_http, acquire_pool,
+    data::QueueKey,
+    models::{
+        guild_settings::GuildSettings,
+        quarantined_member::{CreateQuarantinedMember, QuarantinedMember},
+    },
+    schema::quarantined_members,
+    util::{get_pool_from_serenity, parse_duration_to_seconds},
+    ConnectionPool, Error,
+};
+
+use super::member_log::send_member_log;
+
+pub const ROLE_SETTING: &str = "quarantine_role";
+pub const ACCOUNT_AGE_SETTING: &str = "quarantine_account_age";
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "fang::serde")]
+pub struct ReleaseQuarantine {
+    guild_id: u64,
+    user_id: u64,
+    #[serde(skip)]
+    duration: u64,
+}
+
+impl ReleaseQuarantine {
+    pub fn new<G: Into<GuildId>, U: Into<UserId>>(guild: G, user: U, duration: u64) -> Self {
+        ReleaseQuarantine {
+            guild_id: guild.into().get(),
+            user_id: user.into().get(),
+            duration,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl AsyncRunnable for ReleaseQuarantine {
+    async fn run(&self, _queue: &dyn AsyncQueueable) -> Result<(), FangError> {
+        release_impl(
+            &acquire_cache_http(),
+            &acquire_pool(),
+            GuildId::new(self.guild_id),
+            UserId::new(self.user_id),
+            "The account is old enough",
+        )
+        .await
+        .map_err(|x| FangError {
+            description: x.to_string(),
+        })?;
+        Ok(())
+    }
+
+    fn uniq(&self) -> bool {
+        true
+    }
+
+    fn cron(&self) -> Option<Scheduled> {
+        Some(Scheduled::ScheduleOnce(
+            chrono::Utc::now() + std::time::Duration::from_secs(self.duration),
+        ))
+    }
+
+    fn max_retries(&self) -> i32 {
+        3
+    }
+}
+
+fn get_role<G: Into<GuildId>>(pool: &ConnectionPool, guild: G) -> Option<RoleId> {
+    GuildSettings::get(pool, guild, ROLE_SETTING).map(|x| RoleId::new(x.parse().unwrap()))
+}
+
+/// Remember the role the member got, so that role is removed on release even if the setting
+/// changes in between.
+fn store_role(
+    pool: &ConnectionPool,
+    guild: GuildId,
+    user: UserId,
+    role: RoleId,
+) -> Result<(), Error> {
+    let create = CreateQuarantinedMember::new(guild, user, role);
+    let role: i64 = role.get().try_into().unwrap();
+    insert_into(quarantined_members::table)
+        .values(&create)
+        .on_conflict((quarantined_members::guild, quarantined_members::member))
+        .do_update()
+        .set(quarantined_members::role.eq(role))
+        .execute(&mut pool.get()?)?;
+    Ok(())
+}
+
+/// Remove the quarantine role the member got from the member, returns `false` if quarantine is
+/// disabled.
+pub async fn release_impl<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    user: UserId,
+    reason: &str,
+) -> Result<bool, Error> {
+    let guild_id: i64 = guild.get().try_into().unwrap();
+    let user_id: i64 = user.get().try_into().unwrap();
+    let quarantined: Option<QuarantinedMember> = quarantined_members::table
+        .filter(quarantined_members::guild.eq(guild_id))
+        .filter(quarantined_members::member.eq(user_id))
+        .select(QuarantinedMember::as_select())
+        .first(&mut pool.get()?)
+        .optional()?;
+    // members quarantined before the role was stored got the current role.
+    let Some(role) = quarantined
+        .map(|x| x.role_id())
+        .or_else(|| get_role(pool, guild))
+    else {
+        return Ok(false);
+    };
+    let member = guild.member(cx, user).await?;
+    let has_role = member.roles.contains(&role);
+    if has_role {
+        cx.http()
+            .remove_member_role(guild, user, role, Some(reason))
+            .await?;
+    }
+    delete(quarantined_members::table)
+        .filter(quarantined_members::guild.eq(guild_id))
+        .filter(quarantined_members::member.eq(user_id))
+        .execute(&mut pool.get()?)?;
+    if !has_role {
+        return Ok(true);
+    }
+    send_member_log(
+        cx,
+        pool,
+        guild,
+        CreateEmbed::new()
+            .color(Colour::DARK_GREEN)
+            .title("🔓 Released from Quarantine")
+            .description(format!("<@{}>", user))
+            .field("Reason", reason, false)
+            .footer(CreateEmbedFooter::new(format!("User ID: {}", user))),
+    )
+    .await;
+    Ok(true)
+}
+
+pub async fn handle_member_addition(cx: Context, member: Member) {
+    if member.user.bot {
+        return;
+    }
+    let guild_id = member.guild_id;
+    let pool = get_pool_from_serenity(&cx).await;
+    let Some(role) = get_role(&pool, guild_id) else {
+        return;
+    };
+    let Some(min_age) = GuildSettings::get(&pool, guild_id, ACCOUNT_AGE_SETTING) else {
+        return;
+    };
+    let Ok(min_age_secs) = parse_duration_to_seconds(&min_age) else {
+        return;
+    };
+    let created_at = member.user.id.created_at().unix_timestamp();
+    let age = chrono::Utc::now().timestamp() - created_at;
+    let remaining: u64 = match (min_age_secs as i64 - age).try_into() {
+        Ok(x) if x > 0 => x,
+        _ => return,
+    };
+    if let Err(err) = cx
+        .http
+        .add_member_role(
+            guild_id,
+            member.user.id,
+            role,
+            Some(&format!("Account is younger than {}", min_age)),
+        )
+        .await
+    {
+        log::warn!("Unable to quarantine new account: {err}");
+        return;
+    }
+    if let Err(err) = store_role(&pool, guild_id, member.user.id, role) {
+        log::warn!("Unable to store quarantine role: {err}");
+    }
+    let queue = cx.data.read().await.get::<QueueKey>().unwrap().clone();
+    if let Err(err) = queue
+        .schedule_task(&ReleaseQuarantine::new(guild_id, member.user.id, remaining))
+        .await
+    {
+        log::warn!("Unable to schedule quarantine release: {err}");
+    }
+    send_member_log(
+        &cx,
+        &pool,
+        guild_id,
+        CreateEmbed::new()
+            .color(Colour::ORANGE)
+            .title("🔒 Quarantined")
+            .description(format!("<@{}>", member.user.id))
+            .fields([
+                ("Account Created", format!("<t:{}:R>", created_at), true),
+                (
+                    "Released",
+                    format!("<t:{}:R>", created_at + min_age_secs as i64),
+                    true,
+                ),
+            ])
+            .footer(CreateEmbedFooter::new(format!(
+                "User ID: {}",
+                member.user.id
+            ))),
+    )
+    .await;
+}
@@ -0,0 +1,475 @@
+use dashmap::{DashMap, DashSet};
+use diesel::{delete, insert_into, ExpressionMethods, QueryDsl, RunQueryDsl, SelectableHelper};
+use fang::{async_trait, typetag, AsyncQueue, AsyncQueueable, AsyncRunnable, FangError, Scheduled};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use serenity::all::{
+    CacheHttp, ChannelId, Colour, Context, CreateAllowedMentions, CreateEmbed, CreateMessage,
+    EditChannel, GuildId, Member, RoleId, UserId,
+};
+use uuid::Uuid;
+
+use crate::{
+    acquire_cache_http, acquire_pool,
+    data::QueueKey,
+    models::{
+        guild_settings::GuildSettings,
+        raid_change::{CreateRaidChange, RaidChange, RaidChangeKind},
+    },
+    schema::raid_changes,
+    util::{get_pool_from_serenity, parse_duration_to_seconds},
+    ConnectionPool, Error,
+};
+
+pub const JOIN_LIMIT_SETTING: &str = "raid_join_limit";
+pub const JOIN_WINDOW_SETTING: &str = "raid_join_window";
+pub const SLOWMODE_CHANNELS_SETTING: &str = "raid_slowmode_channels";
+pub const SLOWMODE_SETTING: &str = "raid_slowmode";
+pub const QUARANTINE_ROLE_SETTING: &str = "raid_quarantine_role";
+pub const ALERT_CHANNEL_SETTING: &str = "raid_alert_channel";
+pub const ALERT_ROLE_SETTING: &str = "raid_alert_role";
+pub const DURATION_SETTING: &str = "raid_duration";
+/// Set to the unix timestamp raid mode was started at while it is active.
+const ACTIVE_SETTING: &str = "raid_mode";
+
+pub const DEFAULT_SLOWMODE: u16 = 30;
+pub const DEFAULT_DURATION: &str = "30m";
+
+lazy_static! {
+    static ref RECENT_JOINS: DashMap<GuildId, Vec<(i64, UserId)>> = DashMap::new();
+    /// Guilds raid mode is being started in, they count as in raid mode so joins in the meantime
+    /// are quarantined and raid mode isn't started twice.
+    static ref STARTING: DashSet<GuildId> = DashSet::new();
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(crate = "fang::serde")]
+pub struct EndRaidMode {
+    guild_id: u64,
+    #[serde(skip)]
+    duration: u64,
+}
+
+impl EndRaidMode {
+    pub fn new<G: Into<GuildId>>(guild: G, duration: u64) -> Self {
+        EndRaidMode {
+            guild_id: guild.into().get(),
+            duration,
+        }
+    }
+}
+
+#[typetag::serde]
+#[async_trait]
+impl AsyncRunnable for EndRaidMode {
+    async fn run(&self, _queue: &dyn AsyncQueueable) -> Result<(), FangError> {
+        end_raid_mode(
+            &acquire_cache_http(),
+            &acquire_pool(),
+            GuildId::new(self.guild_id),
+            "Raid mode expired",
+        )
+        .await
+        .map_err(|x| FangError {
+            description: x.to_string(),
+        })?;
+        Ok(())
+    }
+
+    fn uniq(&self) -> bool {
+        true
+    }
+
+    fn cron(&self) -> Option<Scheduled> {
+        Some(Scheduled::ScheduleOnce(
+            chrono::Utc::now() + std::time::Duration::from_secs(self.duration),
+        ))
+    }
+
+    fn max_retries(&self) -> i32 {
+        3
+    }
+}
+
+pub fn is_active<G: Into<GuildId>>(pool: &ConnectionPool, guild: G) -> bool {
+    let guild = guild.into();
+    STARTING.contains(&guild) || GuildSettings::get(pool, guild, ACTIVE_SETTING).is_some()
+}
+
+fn get_number<G: Into<GuildId>>(pool: &ConnectionPool, guild: G, key: &str) -> Option<usize> {
+    GuildSettings::get(pool, guild, key)
+        .and_then(|x| x.parse().ok())
+        .filter(|x| *x > 0)
+}
+
+fn get_quarantine_role<G: Into<GuildId>>(pool: &ConnectionPool, guild: G) -> Option<RoleId> {
+    GuildSettings::get(pool, guild, QUARANTINE_ROLE_SETTING)
+        .and_then(|x| x.parse().ok())
+        .map(RoleId::new)
+}
+
+/// Whether the error means the target of a raid change is gone, so there is nothing to revert.
+fn is_unknown_target(err: &serenity::Error) -> bool {
+    // Unknown Channel, Unknown Member and Unknown Role.
+    matches!(
+        err,
+        serenity::Error::Http(serenity::all::HttpError::UnsuccessfulRequest(x))
+            if [10003, 10007, 10011].contains(&x.error.code)
+    )
+}
+
+async fn send_alert<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    embed: CreateEmbed,
+) {
+    let Some(channel) = GuildSettings::get(pool, guild, ALERT_CHANNEL_SETTING)
+        .or(GuildSettings::get(pool, guild, "moderation_log_channel"))
+        .map(|x| ChannelId::new(x.parse().unwrap()))
+    else {
+        return;
+    };
+    let mut message = CreateMessage::new().embed(embed);
+    if let Some(role) =
+        GuildSettings::get(pool, guild, ALERT_ROLE_SETTING).map(|x| RoleId::new(x.parse().unwrap()))
+    {
+        message = message
+            .content(format!("<@&{}>", role))
+            .allowed_mentions(CreateAllowedMentions::new().roles([role]));
+    }
+    if let Err(err) = channel.send_message(cx, message).await {
+        log::warn!("Unable to send raid alert: {err:?}");
+    }
+}
+
+/// Record the change before applying it, so it can't be missed when reverting.
+fn record_change(pool: &ConnectionPool, change: CreateRaidChange) -> Result<Uuid, Error> {
+    Ok(insert_into(raid_changes::table)
+        .values(change)
+        .returning(raid_changes::id)
+        .get_result(&mut pool.get()?)?)
+}
+
+/// Drop the record of a change that couldn't be applied.
+fn forget_change(pool: &ConnectionPool, id: Uuid) -> Result<(), Error> {
+    delete(raid_changes::table.find(id)).execute(&mut pool.get()?)?;
+    Ok(())
+}
+
+/// Give the quarantine role to the user and record it so it's removed when raid mode ends.
+async fn quarantine<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    user: UserId,
+) -> Result<bool, Error> {
+    let Some(role) = get_quarantine_role(pool, guild) else {
+        return Ok(false);
+    };
+    let id = record_change(
+        pool,
+        CreateRaidChange::new(
+            guild,
+            RaidChangeKind::Quarantine,
+            user.get(),
+            None,
+            Some(role),
+        ),
+    )?;
+    if let Err(err) = cx
+        .http()
+        .add_member_role(guild, user, role, Some("Quarantined during raid mode"))
+        .await
+    {
+        forget_change(pool, id)?;
+        return Err(err.into());
+    }
+    Ok(true)
+}
+
+/// Start raid mode, recording every change so [`end_raid_mode`] can revert it.
+pub async fn start_raid_mode<T: CacheHttp>(
+    cx: &T,
+    state: (&ConnectionPool, &AsyncQueue),
+    guild: GuildId,
+    joiners: &[UserId],
+    reason: &str,
+) -> Result<String, Error> {
+    let pool = state.0;
+    if is_active(pool, guild) || !STARTING.insert(guild) {
+        return Ok("Raid mode is already active.".to_string());
+    }
+    let applied = async {
+        let slowmode = GuildSettings::get(pool, guild, SLOWMODE_SETTING)
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(DEFAULT_SLOWMODE);
+        let mut slowed = 0;
+        for channel in GuildSettings::get_ids(pool, guild, SLOWMODE_CHANNELS_SETTING) {
+            let channel = ChannelId::new(channel);
+            let previous = match channel.to_channel(cx).await.map(|x| x.guild()) {
+                Ok(Some(channel)) => channel.rate_limit_per_user.unwrap_or_default(),
+                _ => {
+                    log::warn!("Unable to find raid slowmode channel {}", channel);
+                    continue;
+                }
+            };
+            if previous >= slowmode {
+                continue;
+            }
+            let id = record_change(
+                pool,
+                CreateRaidChange::new(
+                    guild,
+                    RaidChangeKind::Slowmode,
+                    channel.get(),
+                    Some(previous.into()),
+                    None,
+                ),
+            )?;
+            if let Err(err) = channel
+                .edit(
+                    cx,
+                    EditChannel::new()
+                        .rate_limit_per_user(slowmode)
+                        .audit_log_reason("Raid mode started"),
+                )
+                .await
+            {
+                log::warn!("Unable to apply raid slowmode: {err}");
+                forget_change(pool, id)?;
+                continue;
+            }
+            slowed += 1;
+        }
+        let mut quarantined = 0;
+        for user in joiners {
+            match quarantine(cx, pool, guild, *user).await {
+                Ok(true) => quarantined += 1,
+                Ok(false) => break,
+                Err(err) => log::warn!("Unable to quarantine raider: {err}"),
+            }
+        }
+        let duration = GuildSettings::get(pool, guild, DURATION_SETTING)
+            .unwrap_or(DEFAULT_DURATION.to_string());
+        let duration_secs = parse_duration_to_seconds(&duration).unwrap_or_default();
+        if duration_secs > 0 {
+            state
+                .1
+                .schedule_task(&EndRaidMode::new(guild, duration_secs))
+                .await?;
+        }
+        // raid mode is only marked active once everything has been applied.
+        GuildSettings::set(
+            pool,
+            guild,
+            ACTIVE_SETTING,
+            Some(chrono::Utc::now().timestamp().to_string()),
+        )?;
+        Ok::<_, Error>((slowed, quarantined, duration, duration_secs))
+    }
+    .await;
+    let (slowed, quarantined, duration, duration_secs) = match applied {
+        Ok(x) => {
+            STARTING.remove(&guild);
+            x
+        }
+        Err(err) => {
+            // don't leave raid mode half applied.
+            if let Err(err) = revert_changes(cx, pool, guild).await {
+                log::warn!("Unable to roll back raid mode: {err}");
+            }
+            STARTING.remove(&guild);
+            return Err(err);
+        }
+    };
+    let summary = format!(
+        "Slowmode applied to **{}** channels and **{}** members quarantined.",
+        slowed, quarantined
+    );
+    send_alert(
+        cx,
+        pool,
+        guild,
+        CreateEmbed::new()
+            .color(Colour::RED)
+            .title("🚨 Raid Mode Started")
+            .description(format!("{}\n{}", reason, summary))
+            .field(
+                "Ends",
+                if duration_secs > 0 {
+                    format!("In **{}** or with `/raid end`", duration)
+                } else {
+                    "With `/raid end`".to_string()
+                },
+                false,
+            ),
+    )
+    .await;
+    Ok(format!("Raid mode has been started. {}", summary))
+}
+
+/// Revert the recorded changes, returns how many slowmodes were restored and members released.
+/// Changes that couldn't be reverted are kept to try again.
+async fn revert_changes<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+) -> Result<(usize, usize), Error> {
+    let guild_id: i64 = guild.get().try_into().unwrap();
+    let changes: Vec<RaidChange> = raid_changes::table
+        .filter(raid_changes::guild.eq(guild_id))
+        .select(RaidChange::as_select())
+        .load(&mut pool.get()?)?;
+    // changes recorded before the role was stored fall back to the current role.
+    let quarantine_role = get_quarantine_role(pool, guild);
+    let (mut restored, mut released) = (0, 0);
+    let mut reverted = vec![];
+    for change in &changes {
+        let target = TryInto::<u64>::try_into(change.target).unwrap();
+        let res = match change.kind {
+            RaidChangeKind::Slowmode => ChannelId::new(target)
+                .edit(
+                    cx,
+                    EditChannel::new()
+                        .rate_limit_per_user(
+                            change
+                                .previous_value
+                                .and_then(|x| x.try_into().ok())
+                                .unwrap_or_default(),
+                        )
+                        .audit_log_reason("Raid mode ended"),
+                )
+                .await
+                .map(|_| ()),
+            RaidChangeKind::Quarantine => {
+                let role = change
+                    .role
+                    .and_then(|x| x.try_into().ok())
+                    .map(RoleId::new)
+                    .or(quarantine_role);
+                match role {
+                    Some(role) => {
+                        cx.http()
+                            .remove_member_role(
+                                guild,
+                                UserId::new(target),
+                                role,
+                                Some("Raid mode ended"),
+                            )
+                            .await
+                    }
+                    None => {
+                        log::warn!(
+                            "Unable to release {}, the quarantine role is unknown",
+                            target
+                        );
+                        Ok(())
+                    }
+                }
+            }
+        };
+        match res {
+            Ok(_) => match change.kind {
+                RaidChangeKind::Slowmode => restored += 1,
+                RaidChangeKind::Quarantine => released += 1,
+            },
+            // the channel or member is gone, which doesn't need reverting anymore.
+            Err(err) if is_unknown_target(&err) => {}
+            Err(err) => {
+                log::warn!("Unable to revert raid change: {err}");
+                continue;
+            }
+        }
+        reverted.push(change.id);
+    }
+    delete(raid_changes::table)
+        .filter(raid_changes::id.eq_any(&reverted))
+        .execute(&mut pool.get()?)?;
+    let failed = changes.len() - reverted.len();
+    if failed > 0 {
+        return Err(format!(
+            "{} raid changes couldn't be reverted, please try again later.",
+            failed
+        )
+        .into());
+    }
+    Ok((restored, released))
+}
+
+/// End raid mode and revert everything it changed.
+pub async fn end_raid_mode<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    reason: &str,
+) -> Result<String, Error> {
+    if !is_active(pool, guild) {
+        return Ok("Raid mode isn't active.".to_string());
+    }
+    if STARTING.contains(&guild) {
+        // an error keeps the scheduled end.
+        return Err("Raid mode is still being started, try again in a moment.".into());
+    }
+    // raid mode stays active if anything is left, so it can be ended again.
+    let (restored, released) = revert_changes(cx, pool, guild).await?;
+    GuildSettings::set(pool, guild, ACTIVE_SETTING, None::<String>)?;
+    RECENT_JOINS.remove(&guild);
+    let summary = format!(
+        "Slowmode reverted in **{}** channels and **{}** members released.",
+        restored, released
+    );
+    send_alert(
+        cx,
+        pool,
+        guild,
+        CreateEmbed::new()
+            .color(Colour::DARK_GREEN)
+            .title("✅ Raid Mode Ended")
+            .description(format!("{}\n{}", reason, summary)),
+    )
+    .await;
+    Ok(format!("Raid mode has been ended. {}", summary))
+}
+
+pub async fn handle_member_addition(cx: Context, member: Member) {
+    if member.user.bot {
+        return;
+    }
+    let guild_id = member.guild_id;
+    let pool = get_pool_from_serenity(&cx).await;
+    if is_active(&pool, guild_id) {
+        if let Err(err) = quarantine(&cx, &pool, guild_id, member.user.id).await {
+            log::warn!("Unable to quarantine raider: {err}");
+        }
+        return;
+    }
+    let (Some(limit), Some(window)) = (
+        get_number(&pool, guild_id, JOIN_LIMIT_SETTING),
+        get_number(&pool, guild_id, JOIN_WINDOW_SETTING),
+    ) else {
+        return;
+    };
+    let now = chrono::Utc::now().timestamp();
+    let joiners: Vec<UserId> = {
+        let mut joins = RECENT_JOINS.entry(guild_id).or_default();
+        joins.retain(|x| x.0 > now - window as i64);
+        joins.push((now, member.user.id));
+        if joins.len() < limit {
+            return;
+        }
+        std::mem::take(&mut *joins)
+            .into_iter()
+            .map(|x| x.1)
+            .collect()
+    };
+    let queue = cx.data.read().await.get::<QueueKey>().unwrap().clone();
+    let reason = format!(
+        "**{}** members joined within **{}** seconds.",
+        joiners.len(),
+        window
+    );
+    if let Err(err) = start_raid_mode(&cx, (&pool, &queue), guild_id, &joiners, &reason).await {
+        log::warn!("Unable to start raid mode: {err}");
+    }
+}
@@ -1,53 +1,452 @@
+use chrono::NaiveDateTime;
+use dashmap::{mapref::entry::Entry, DashMap};
 use diesel::{
-    dsl::{delete, insert_into},
-    query_dsl::methods::{FilterDsl, LimitDsl, SelectDsl},
-    ExpressionMethods, RunQueryDsl, SelectableHelper,
+    dsl::{delete, insert_into, now, update},
+    query_dsl::methods::{FilterDsl, FindDsl, LimitDsl, SelectDsl},
+    ExpressionMethods, OptionalExtension, RunQueryDsl, SelectableHelper,
 };
+use fang::{async_trait, typetag, AsyncQueueable, AsyncRunnable, FangError, Scheduled};
+use serde::{Deserialize, Serialize};
 use serenity::all::{
-    CacheHttp, ChannelId, ComponentInteractionDataKind, Context, CreateChannel,
-    CreateInteractionResponse, CreateInteractionResponseFollowup, CreateInteractionResponseMessage,
-    CreateMessage, EditMember, GuildChannel, GuildId, Interaction, Member, User, VoiceState,
+    ActionRowComponent, ButtonStyle, CacheHttp, ChannelId, Colour, ComponentInteraction,
+    ComponentInteractionDataKind, Context, CreateActionRow, CreateButton, CreateChannel,
+    CreateEmbed, CreateInputText, CreateInteractionResponse, CreateInteractionResponseFollowup,
+    CreateInteractionResponseMessage, CreateMessage, CreateModal, CreateSelectMenu,
+    CreateSelectMenuKind, CreateSelectMenuOption, EditChannel, EditMember, GuildChannel, GuildId,
+    InputTextStyle, Interaction, Member, ModalInteraction, PermissionOverwrite,
+    PermissionOverwriteType, Permissions, ReactionType, User, UserId, VoiceState,
 };
 
+use lazy_static::lazy_static;
+
 use crate::{
+    acquire_cache_http, acquire_pool,
     data::ConnectionPoolKey,
     models::{
-        guild_settings::GuildSettings,
         voice_channel::{CreateVoiceChannel, VoiceChannel},
+        voice_creator::VoiceCreator,
+        voice_preference::{CreateVoicePreference, VoicePreference},
     },
-    schema::voice_channels,
+    schema::{voice_channels, voice_creators, voice_preferences},
     util::get_pool_from_serenity,
     ConnectionPool, Error,
 };
 
-pub fn default_channel_name_for_member(member: &Member) -> String {
-    member
+lazy_static! {
+    /// Channels being cleaned up and whether another event for the channel arrived meanwhile,
+    /// the running cleanup checks the channel again instead of running concurrently.
+    static ref PENDING_CLEANUPS: DashMap<ChannelId, bool> = DashMap::new();
+}
+
+/// Prefix of the custom IDs of the control panel components.
+const PANEL_PREFIX: &str = "voice_panel:";
+
+pub const CLAIM_DELAY_SETTING: &str = "temp_voice_claim_delay";
+pub const DEFAULT_CLAIM_DELAY: &str = "5m";
+
+/// Fill in the name pattern of a creator channel, names are cut off at Discord's limit.
+pub fn render_channel_name(pattern: &str, member: &Member, number: i32) -> String {
+    let nick = member
         .nick
         .clone()
-        .unwrap_or(member.user.display_name().to_string())
+        .unwrap_or(member.user.display_name().to_string());
+    // nicks are replaced last, they might contain the other placeholders.
+    pattern
+        .replace("{n}", &number.to_string())
+        .replace("{username}", &member.user.name)
+        .replace("{nick}", &nick)
+        .chars()
+        .take(100)
+        .collect()
+}
+
+pub fn get_voice_creator<G: Into<GuildId>, C: Into<ChannelId>>(
+    pool: &ConnectionPool,
+    guild: G,
+    channel: C,
+) -> Result<Option<VoiceCreator>, Error> {
+    Ok(voice_creators::table
+        .filter(voice_creators::channel.eq(TryInto::<i64>::try_into(channel.into().get()).unwrap()))
+        .filter(voice_creators::guild.eq(TryInto::<i64>::try_into(guild.into().get()).unwrap()))
+        .select(VoiceCreator::as_select())
+        .get_result(&mut pool.get()?)
+        .optional()?)
+}
+
+/// The lowest number not taken by the existing channels of the creator channel.
+fn next_channel_number<C: Into<ChannelId>>(
+    pool: &ConnectionPool,
+    creator_channel: C,
+) -> Result<i32, Error> {
+    let taken: Vec<Option<i32>> = voice_channels::table
+        .filter(
+            voice_channels::creator_channel
+                .eq(TryInto::<i64>::try_into(creator_channel.into().get()).unwrap()),
+        )
+        .select(voice_channels::number)
+        .load(&mut pool.get()?)?;
+    Ok((1..).find(|x| !taken.contains(&Some(*x))).unwrap())
+}
+
+/// A channel setting remembered for the channels a member creates.
+pub enum VoicePreferenceChange {
+    Name(String),
+    /// `0` removes the limit.
+    UserLimit(u32),
+    /// In bits per second.
+    Bitrate(u32),
+    /// `None` lets Discord pick the region.
+    Region(Option<String>),
+    /// Only permitted members can join.
+    Locked(bool),
+    /// Only permitted members can see the channel.
+    Hidden(bool),
+    /// Let the member see and join the channel, this lifts a rejection.
+    Permit(UserId),
+    /// Keep the member out of the channel, this revokes a permission.
+    Reject(UserId),
+}
+
+pub fn get_voice_preference<G: Into<GuildId>, U: Into<UserId>>(
+    pool: &ConnectionPool,
+    guild: G,
+    member: U,
+) -> Result<Option<VoicePreference>, Error> {
+    Ok(voice_preferences::table
+        .find((
+            TryInto::<i64>::try_into(guild.into().get()).unwrap(),
+            TryInto::<i64>::try_into(member.into().get()).unwrap(),
+        ))
+        .select(VoicePreference::as_select())
+        .get_result(&mut pool.get()?)
+        .optional()?)
+}
+
+pub fn save_voice_preference<G: Into<GuildId>, U: Into<UserId>>(
+    pool: &ConnectionPool,
+    guild: G,
+    member: U,
+    change: VoicePreferenceChange,
+) -> Result<(), Error> {
+    let guild = guild.into();
+    let member = member.into();
+    let create = CreateVoicePreference::new(guild, member);
+    let conflict = (voice_preferences::guild, voice_preferences::member);
+    let updated_at = voice_preferences::updated_at.eq(now);
+    let mut conn = pool.get()?;
+    match change {
+        VoicePreferenceChange::Name(name) => insert_into(voice_preferences::table)
+            .values((&create, voice_preferences::name.eq(&name)))
+            .on_conflict(conflict)
+            .do_update()
+            .set((voice_preferences::name.eq(&name), updated_at))
+            .execute(&mut conn)?,
+        VoicePreferenceChange::UserLimit(limit) => {
+            let limit = TryInto::<i32>::try_into(limit).unwrap();
+            insert_into(voice_preferences::table)
+                .values((&create, voice_preferences::user_limit.eq(limit)))
+                .on_conflict(conflict)
+                .do_update()
+                .set((voice_preferences::user_limit.eq(limit), updated_at))
+                .execute(&mut conn)?
+        }
+        VoicePreferenceChange::Bitrate(bitrate) => {
+            let bitrate = TryInto::<i32>::try_into(bitrate).unwrap();
+            insert_into(voice_preferences::table)
+                .values((&create, voice_preferences::bitrate.eq(bitrate)))
+                .on_conflict(conflict)
+                .do_update()
+                .set((voice_preferences::bitrate.eq(bitrate), updated_at))
+                .execute(&mut conn)?
+        }
+        VoicePreferenceChange::Region(region) => insert_into(voice_preferences::table)
+            .values((&create, voice_preferences::region.eq(&region)))
+            .on_conflict(conflict)
+            .do_update()
+            .set((voice_preferences::region.eq(&region), updated_at))
+            .execute(&mut conn)?,
+        VoicePreferenceChange::Locked(locked) => insert_into(voice_preferences::table)
+            .values((&create, voice_preferences::locked.eq(locked)))
+            .on_conflict(conflict)
+            .do_update()
+            .set((voice_preferences::locked.eq(locked), updated_at))
+            .execute(&mut conn)?,
+        VoicePreferenceChange::Hidden(hidden) => insert_into(voice_preferences::table)
+            .values((&create, voice_preferences::hidden.eq(hidden)))
+            .on_conflict(conflict)
+            .do_update()
+            .set((voice_preferences::hidden.eq(hidden), updated_at))
+            .execute(&mut conn)?,
+        VoicePreferenceChange::Permit(user) | VoicePreferenceChange::Reject(user) => {
+            let (mut permitted, mut rejected) = get_voice_preference(pool, guild, member)?
+                .map(|x| (x.permitted, x.rejected))
+                .unwrap_or_default();
+            let user = TryInto::<i64>::try_into(user.get()).unwrap();
+            permitted.retain(|x| *x != user);
+            rejected.retain(|x| *x != user);
+            if matches!(change, VoicePreferenceChange::Permit(_)) {
+                permitted.push(user);
+            } else {
+                rejected.push(user);
+            }
+            let access = (
+                voice_preferences::permitted.eq(&permitted),
+                voice_preferences::rejected.eq(&rejected),
+            );
+            insert_into(voice_preferences::table)
+                .values((&create, access))
+                .on_conflict(conflict)
+                .do_update()
+                .set((access, updated_at))
+                .execute(&mut conn)?
+        }
+    };
+    Ok(())
+}
+
+/// Forget the channel settings of the member, returns `false` if there were none.
+pub fn reset_voice_preference<G: Into<GuildId>, U: Into<UserId>>(
+    pool: &ConnectionPool,
+    guild: G,
+    member: U,
+) -> Result<bool, Error> {
+    let deleted = delete(voice_preferences::table)
+        .filter(voice_preferences::guild.eq(TryInto::<i64>::try_into(guild.into().get()).unwrap()))
+        .filter(
+            voice_preferences::member.eq(TryInto::<i64>::try_into(member.into().get()).unwrap()),
+        )
+        .execute(&mut pool.get()?)?;
+    Ok(deleted > 0)
+}
+
+/// Whether the owner changed any privacy settings, channels of other owners stay in sync with
+/// their category.
+fn is_private(preference: &VoicePreference) -> bool {
+    preference.locked
+        || preference.hidden
+        || !preference.permitted.is_empty()
+        || !preference.rejected.is_empty()
+}
+
+/// Apply the privacy settings of the owner to the permission overwrites of a channel.
+///
+/// Only the VIEW_CHANNEL and CONNECT bits of @everyone and members are managed here, they are
+/// reset to the ones of the category before the settings are applied. Everything else, e.g. the
+/// overwrites inherited from the category, is kept as it is.
+pub fn privacy_overwrites(
+    guild: GuildId,
+    owner: UserId,
+    bot: Option<UserId>,
+    preference: Option<&VoicePreference>,
+    current: &[PermissionOverwrite],
+    category: &[PermissionOverwrite],
+) -> Vec<PermissionOverwrite> {
+    let access = Permissions::VIEW_CHANNEL | Permissions::CONNECT;
+    let everyone_role = PermissionOverwriteType::Role(guild.everyone_role());
+    let is_managed = |kind: &PermissionOverwriteType| {
+        *kind == everyone_role || matches!(kind, PermissionOverwriteType::Member(_))
+    };
+    let inherited = |kind: PermissionOverwriteType| category.iter().find(|x| x.kind == kind);
+    let mut overwrites = current.to_vec();
+    for overwrite in overwrites.iter_mut().filter(|x| is_managed(&x.kind)) {
+        let (allow, deny) = inherited(overwrite.kind)
+            .map(|x| (x.allow & access, x.deny & access))
+            .unwrap_or_default();
+        overwrite.allow = (overwrite.allow - access) | allow;
+        overwrite.deny = (overwrite.deny - access) | deny;
+    }
+    let mut set = |kind: PermissionOverwriteType, allow: Permissions, deny: Permissions| {
+        let index = match overwrites.iter().position(|x| x.kind == kind) {
+            Some(index) => index,
+            None => {
+                overwrites.push(PermissionOverwrite {
+                    allow: Permissions::empty(),
+                    deny: Permissions::empty(),
+                    kind,
+                });
+                overwrites.len() - 1
+            }
+        };
+        let overwrite = &mut overwrites[index];
+        overwrite.allow = (overwrite.allow - deny) | allow;
+        overwrite.deny = (overwrite.deny - allow) | deny;
+    };
+    if let Some(preference) = preference.filter(|x| is_private(x)) {
+        let mut everyone = Permissions::empty();
+        if preference.locked {
+            everyone |= Permissions::CONNECT;
+        }
+        if preference.hidden {
+            everyone |= Permissions::VIEW_CHANNEL;
+        }
+        set(everyone_role, Permissions::empty(), everyone);
+        let ids = |ids: &Vec<i64>| {
+            ids.iter()
+                .map(|x| UserId::new((*x).try_into().unwrap()))
+                .filter(|x| *x != owner && Some(*x) != bot)
+                .collect::<Vec<UserId>>()
+        };
+        // the owner and the bot are always let in, locking them out would make the channel
+        // unusable.
+        for id in bot.into_iter().chain([owner]) {
+            set(
+                PermissionOverwriteType::Member(id),
+                access,
+                Permissions::empty(),
+            );
+        }
+        for id in ids(&preference.permitted) {
+            set(
+                PermissionOverwriteType::Member(id),
+                access,
+                Permissions::empty(),
+            );
+        }
+        for id in ids(&preference.rejected) {
+            set(
+                PermissionOverwriteType::Member(id),
+                Permissions::empty(),
+                access,
+            );
+        }
+    }
+    // empty overwrites are the same as none, unless the category has them as well.
+    overwrites.retain(|x| !x.allow.is_empty() || !x.deny.is_empty() || inherited(x.kind).is_some());
+    overwrites
+}
+
+/// The permission overwrites of the category of the channel.
+async fn category_overwrites<T: CacheHttp>(
+    cx: &T,
+    category: Option<ChannelId>,
+) -> Result<Vec<PermissionOverwrite>, Error> {
+    let Some(category) = category else {
+        return Ok(vec![]);
+    };
+    Ok(category
+        .to_channel(cx)
+        .await?
+        .guild()
+        .map(|x| x.permission_overwrites)
+        .unwrap_or_default())
+}
+
+/// Apply the privacy settings of the owner to the channel, see [`privacy_overwrites`].
+pub async fn sync_voice_permissions<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    channel: ChannelId,
+    owner: UserId,
+) -> Result<(), Error> {
+    let bot = cx.cache().map(|x| x.current_user().id);
+    let preference = get_voice_preference(pool, guild, owner)?;
+    let Some(current) = channel.to_channel(cx).await?.guild() else {
+        return Ok(());
+    };
+    let overwrites = privacy_overwrites(
+        guild,
+        owner,
+        bot,
+        preference.as_ref(),
+        &current.permission_overwrites,
+        &category_overwrites(cx, current.parent_id).await?,
+    );
+    if overwrites == current.permission_overwrites {
+        return Ok(());
+    }
+    if overwrites.is_empty() {
+        // an empty list can't be sent, remove the remaining overwrites one by one.
+        for overwrite in current.permission_overwrites {
+            channel.delete_permission(cx.http(), overwrite.kind).await?;
+        }
+        return Ok(());
+    }
+    channel
+        .edit(
+            cx,
+            EditChannel::new()
+                .permissions(overwrites)
+                .audit_log_reason("Privacy settings of the owner updated"),
+        )
+        .await?;
+    Ok(())
 }
 
-pub async fn create_temp_voice_channel<U: CacheHttp, V: Into<GuildId>, W: AsRef<str>>(
+pub async fn create_temp_voice_channel<U: CacheHttp>(
     pool: &ConnectionPool,
     http: &U,
-    guild: V,
-    creator: &User,
-    name: W,
+    member: &Member,
+    template: &VoiceCreator,
     category: Option<ChannelId>,
 ) -> Result<GuildChannel, Error> {
+    let guild = member.guild_id;
+    let creator = &member.user;
     let reason = format!("Created by @{} ({})", creator.name, creator.id.get());
-    let mut create_channel = CreateChannel::new(name.as_ref().to_string())
-        .kind(serenity::all::ChannelType::Voice)
-        .audit_log_reason(&reason);
-    if let Some(category) = category {
-        create_channel = create_channel.category(category);
-    }
-    let guild = guild.into();
-    let channel = guild.create_channel(http, create_channel).await;
+    let number = next_channel_number(pool, template.channel_id())?;
+    let name = render_channel_name(&template.name_pattern, member, number);
+    let preference = get_voice_preference(pool, guild, creator.id)?;
+    // channels without privacy settings are left in sync with their category.
+    let overwrites = match preference.as_ref().filter(|x| is_private(x)) {
+        Some(preference) => {
+            let inherited = category_overwrites(http, category).await?;
+            privacy_overwrites(
+                guild,
+                creator.id,
+                http.cache().map(|x| x.current_user().id),
+                Some(preference),
+                &inherited,
+                &inherited,
+            )
+        }
+        None => vec![],
+    };
+    let build = |preference: Option<&VoicePreference>| {
+        let name = preference
+            .and_then(|x| x.name.clone())
+            .unwrap_or(name.clone());
+        let mut create_channel = CreateChannel::new(name)
+            .kind(template.kind.channel_type())
+            .audit_log_reason(&reason);
+        if !overwrites.is_empty() {
+            create_channel = create_channel.permissions(overwrites.clone());
+        }
+        if let Some(category) = category {
+            create_channel = create_channel.category(category);
+        }
+        let user_limit = preference
+            .and_then(|x| x.user_limit)
+            .or(template.user_limit);
+        if let Some(limit) = user_limit.filter(|x| *x > 0) {
+            create_channel = create_channel.user_limit(limit.try_into().unwrap());
+        }
+        if let Some(bitrate) = preference.and_then(|x| x.bitrate).or(template.bitrate) {
+            create_channel = create_channel.bitrate(bitrate.try_into().unwrap());
+        }
+        if let Some(region) = preference.and_then(|x| x.region.clone()) {
+            create_channel = create_channel.rtc_region(region);
+        }
+        create_channel
+    };
+    let channel = match guild.create_channel(http, build(preference.as_ref())).await {
+        // the saved bitrate or region might not be available anymore, fall back to the template
+        // but keep the privacy settings.
+        Err(err) if preference.is_some() => {
+            log::warn!("Unable to apply voice preferences: {err:?}");
+            guild.create_channel(http, build(None)).await
+        }
+        x => x,
+    };
     match channel {
         Ok(channel) => {
             insert_into(voice_channels::table)
-                .values(&[CreateVoiceChannel::new(&channel, guild, creator)])
+                .values(&[CreateVoiceChannel::new(
+                    &channel,
+                    guild,
+                    creator,
+                    template.channel_id(),
+                    number,
+                )])
                 .execute(&mut pool.get()?)?;
             Ok(channel)
         }
@@ -55,7 +454,207 @@ pub async fn create_temp_voice_channel<U: CacheHttp, V: Into<GuildId>, W: AsRef<
     }
 }
 
-pub async fn handle_voice_state_update(cx: Context, new: VoiceState) {
+/// The user's temp voice channel in the guild, users own one channel per guild at most.
+pub fn get_owned_voice_channel<G: Into<GuildId>, U: Into<UserId>>(
+    pool: &ConnectionPool,
+    guild: G,
+    user: U,
+) -> Result<Option<VoiceChannel>, Error> {
+    Ok(voice_channels::table
+        .filter(voice_channels::guild.eq(TryInto::<i64>::try_into(guild.into().get()).unwrap()))
+        .filter(voice_channels::creator.eq(TryInto::<i64>::try_into(user.into().get()).unwrap()))
+        .select(VoiceChannel::as_select())
+        .first(&mut pool.get()?)
+        .optional()?)
+}
+
+pub fn set_voice_channel_owner<C: Into<ChannelId>, U: Into<UserId>>(
+    pool: &ConnectionPool,
+    channel: C,
+    owner: U,
+) -> Result<(), Error> {
+    update(voice_channels::table)
+        .filter(voice_channels::id.eq(TryInto::<i64>::try_into(channel.into().get()).unwrap()))
+        .set((
+            voice_channels::creator.eq(TryInto::<i64>::try_into(owner.into().get()).unwrap()),
+            voice_channels::owner_left_at.eq(None::<NaiveDateTime>),
+        ))
+        .execute(&mut pool.get()?)?;
+    Ok(())
+}
+
+/// Remember when owners leave their channel, so that it can be claimed after a while.
+fn track_owner_presence(pool: &ConnectionPool, state: &VoiceState) -> Result<(), Error> {
+    let user = TryInto::<i64>::try_into(state.user_id.get()).unwrap();
+    let channel = state
+        .channel_id
+        .map(|x| TryInto::<i64>::try_into(x.get()).unwrap());
+    let mut conn = pool.get()?;
+    if let Some(channel) = channel {
+        update(voice_channels::table)
+            .filter(voice_channels::id.eq(channel))
+            .filter(voice_channels::creator.eq(user))
+            .set(voice_channels::owner_left_at.eq(None::<NaiveDateTime>))
+            .execute(&mut conn)?;
+    }
+    update(voice_channels::table)
+        .filter(voice_channels::creator.eq(user))
+        .filter(voice_channels::id.ne(channel.unwrap_or(0)))
+        .filter(voice_channels::owner_left_at.is_null())
+        .set(voice_channels::owner_left_at.eq(now))
+        .execute(&mut conn)?;
+    Ok(())
+}
+
+/// Delete the temp channel if no one is left in it, the occupancy is read from the cache.
+/// Returns `true` if the channel has been deleted.
+async fn cleanup_channel<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    channel: ChannelId,
+) -> Result<bool, Error> {
+    match PENDING_CLEANUPS.entry(channel) {
+        Entry::Occupied(mut x) => {
+            x.insert(true);
+            return Ok(false);
+        }
+        Entry::Vacant(x) => {
+            x.insert(false);
+        }
+    }
+    loop {
+        let res = try_cleanup_channel(cx, pool, guild, channel).await;
+        if !matches!(res, Ok(false)) {
+            PENDING_CLEANUPS.remove(&channel);
+            return res;
+        }
+        if PENDING_CLEANUPS
+            .remove_if(&channel, |_, dirty| !*dirty)
+            .is_some()
+        {
+            return res;
+        }
+        // the channel changed during the cleanup, e.g. someone left it.
+        PENDING_CLEANUPS.insert(channel, false);
+    }
+}
+
+async fn try_cleanup_channel<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    channel: ChannelId,
+) -> Result<bool, Error> {
+    let id = TryInto::<i64>::try_into(channel.get()).unwrap();
+    let managed = voice_channels::table
+        .find(id)
+        .select(voice_channels::id)
+        .first::<i64>(&mut pool.get()?)
+        .optional()?
+        .is_some();
+    if !managed {
+        return Ok(false);
+    }
+    let Some(occupied) = cx.cache().and_then(|x| x.guild(guild)).map(|x| {
+        x.voice_states
+            .values()
+            .any(|x| x.channel_id == Some(channel))
+    }) else {
+        return Ok(false);
+    };
+    if occupied {
+        return Ok(false);
+    }
+    match channel.delete(cx.http()).await {
+        Ok(_) => {}
+        Err(serenity::Error::Http(serenity::all::HttpError::UnsuccessfulRequest(err)))
+            if err.error.code == 10003 => {}
+        Err(err) => return Err(err.into()),
+    }
+    delete(voice_channels::table)
+        .filter(voice_channels::id.eq(id))
+        .execute(&mut pool.get()?)?;
+    Ok(true)
+}
+
+/// Delete the managed channels that have been left empty and drop the records of the channels
+/// that no longer exist, e.g. while the bot was offline. Only cached guilds are checked.
+pub async fn reconcile_channels<T: CacheHttp>(cx: &T, pool: &ConnectionPool) -> Result<(), Error> {
+    let Some(cache) = cx.cache() else {
+        return Ok(());
+    };
+    let records = voice_channels::table
+        .select(VoiceChannel::as_select())
+        .load(&mut pool.get()?)?;
+    let (mut deleted, mut dropped) = (0, 0);
+    for record in records {
+        let guild = GuildId::new(record.guild.try_into().unwrap());
+        let channel = ChannelId::new(record.id.try_into().unwrap());
+        let Some(exists) = cache
+            .guild(guild)
+            .map(|x| x.channels.contains_key(&channel))
+        else {
+            continue;
+        };
+        if !exists {
+            delete(voice_channels::table)
+                .filter(voice_channels::id.eq(record.id))
+                .execute(&mut pool.get()?)?;
+            log::info!(
+                "Dropped the record of missing temporary voice channel {} in {}.",
+                channel,
+                guild
+            );
+            dropped += 1;
+        } else if cleanup_channel(cx, pool, guild, channel).await? {
+            log::info!(
+                "Deleted empty temporary voice channel {} in {}.",
+                channel,
+                guild
+            );
+            deleted += 1;
+        }
+    }
+    if deleted > 0 || dropped > 0 {
+        log::info!(
+            "Reconciled temporary voice channels, {} deleted and {} stale records dropped.",
+            deleted,
+            dropped
+        );
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(crate = "fang::serde")]
+pub struct ReconcileVoiceChannels {}
+
+#[typetag::serde]
+#[async_trait]
+impl AsyncRunnable for ReconcileVoiceChannels {
+    async fn run(&self, _queue: &dyn AsyncQueueable) -> Result<(), FangError> {
+        reconcile_channels(&acquire_cache_http(), &acquire_pool())
+            .await
+            .map_err(|x| FangError {
+                description: x.to_string(),
+            })
+    }
+
+    fn uniq(&self) -> bool {
+        true
+    }
+
+    fn cron(&self) -> Option<Scheduled> {
+        Some(Scheduled::CronPattern("0 */10 * * * *".to_string()))
+    }
+
+    fn max_retries(&self) -> i32 {
+        3
+    }
+}
+
+pub async fn handle_voice_state_update(cx: Context, old: Option<VoiceState>, new: VoiceState) {
     let pool = cx
         .data
         .read()
@@ -63,119 +662,463 @@ pub async fn handle_voice_state_update(cx: Context, new: VoiceState) {
         .get::<ConnectionPoolKey>()
         .unwrap()
         .clone();
+    if let Err(err) = track_owner_presence(&pool, &new) {
+        log::warn!("Unable to track temp voice owner: {err}");
+    }
+    // the user left a channel, either disconnecting or moving to another one.
+    if let Some(left) = old
+        .and_then(|x| x.channel_id)
+        .filter(|x| new.channel_id != Some(*x))
+    {
+        if let Err(err) = cleanup_channel(&cx, &pool, new.guild_id.unwrap(), left).await {
+            log::warn!("Unable to clean up temporary voice channel: {err:?}");
+        }
+    }
     if let Some(channel_id) = new.channel_id {
         let guild_id = new.guild_id.unwrap();
-        let Some(guild_voice_creator) =
-            GuildSettings::get(&pool, guild_id, "creator_voice_channel")
-        else {
+        let template = match get_voice_creator(&pool, guild_id, channel_id) {
+            Ok(Some(template)) => template,
+            Ok(None) => return,
+            Err(err) => {
+                log::warn!("Unable to get the voice creator: {err}");
+                return;
+            }
+        };
+        let Some(member) = &new.member else {
             return;
         };
-        if channel_id.get().to_string() == guild_voice_creator {
-            if let Some(member) = &new.member {
-                let created_channels = voice_channels::table
-                    .filter(
-                        voice_channels::creator
-                            .eq(TryInto::<i64>::try_into(member.user.id.get()).unwrap()),
-                    )
-                    .limit(1)
-                    .select(VoiceChannel::as_select())
-                    .load(&mut pool.get().unwrap())
-                    .unwrap();
-                let create_channel_and_move_user = async {
-                    let parent_channel = channel_id
+        let created_channels = voice_channels::table
+            .filter(
+                voice_channels::creator.eq(TryInto::<i64>::try_into(member.user.id.get()).unwrap()),
+            )
+            .limit(1)
+            .select(VoiceChannel::as_select())
+            .load(&mut pool.get().unwrap())
+            .unwrap();
+        let create_channel_and_move_user = async {
+            let category = match template.category_id() {
+                Some(category) => Some(category),
+                None => {
+                    channel_id
                         .to_channel(&cx)
                         .await
                         .unwrap()
                         .guild()
                         .unwrap()
-                        .parent_id;
-                    match create_temp_voice_channel(
-                        &pool,
+                        .parent_id
+                }
+            };
+            match create_temp_voice_channel(&pool, &cx, member, &template, category).await {
+                Ok(channel) => {
+                    match member.move_to_voice_channel(&cx, &channel).await {
+                        Ok(_) => {
+                            if let Err(err) = channel
+                                .send_message(&cx, control_panel(member.user.id))
+                                .await
+                            {
+                                log::warn!("Unable to send the control panel: {err:?}");
+                            }
+                        }
+                        Err(_) => {
+                            // user could probably quit the voice channel while creating, just delete the new created channel.
+                            channel.delete(&cx).await.unwrap();
+                        }
+                    };
+                }
+                Err(err) => {
+                    log::warn!("Failed to create voice channel: {err:?}");
+                }
+            }
+        };
+        if created_channels.is_empty() {
+            create_channel_and_move_user.await;
+        } else {
+            // user already created a voice channel before, move the user there.
+            if let Err(serenity::Error::Http(serenity::all::HttpError::UnsuccessfulRequest(err))) =
+                member
+                    .move_to_voice_channel(
                         &cx,
-                        &guild_id,
-                        &member.user,
-                        default_channel_name_for_member(member),
-                        parent_channel,
+                        ChannelId::new(created_channels[0].id.try_into().unwrap()),
                     )
                     .await
-                    {
-                        Ok(channel) => {
-                            match member.move_to_voice_channel(&cx, &channel).await {
-                                Ok(_) => {
-                                    channel.send_message(&cx, CreateMessage::new()
-                                        .content(format!(
-                                            "<@{}> You have just created a voice channel!\n\
-                                            \n\
-                                            You are the owner of this channel.\n\
-                                            The channel will only get deleted once there's no one here.\n\
-                                            If you left the channel while someone is still here, you can come back anytime.\n\
-                                            \n\
-                                            Note that you are unable to create another new channel unless this channel is deleted.",
-                                            member.user.id.get()
-                                        ))
-                                    ).await.expect("Unable to send message");
-                                }
-                                Err(_) => {
-                                    // user could probably quit the voice channel while creating, just delete the new created channel.
-                                    channel.delete(&cx).await.unwrap();
-                                }
-                            };
-                        }
-                        Err(err) => {
-                            log::warn!("Failed to create voice channel: {err:?}");
-                        }
-                    }
-                };
-                if created_channels.is_empty() {
+            {
+                if err.error.code == 10003 {
+                    // the user's channel no longer exists.
+                    delete(voice_channels::table)
+                        .filter(voice_channels::id.eq(created_channels[0].id))
+                        .execute(&mut pool.get().unwrap())
+                        .expect("Unable to delete user's voice channel record.");
                     create_channel_and_move_user.await;
-                } else {
-                    // user already created a voice channel before, move the user there.
-                    if let Err(serenity::Error::Http(
-                        serenity::all::HttpError::UnsuccessfulRequest(err),
-                    )) = member
-                        .move_to_voice_channel(
-                            &cx,
-                            ChannelId::new(created_channels[0].id.try_into().unwrap()),
-                        )
-                        .await
-                    {
-                        if err.error.code == 10003 {
-                            // the user's channel no longer exists.
-                            delete(voice_channels::table)
-                                .filter(voice_channels::id.eq(created_channels[0].id))
-                                .execute(&mut pool.get().unwrap())
-                                .expect("Unable to delete user's voice channel record.");
-                            create_channel_and_move_user.await;
-                        }
-                    }
                 }
             }
         }
-    } else {
-        // a user is leaving the voice channel, let's clean up all temporary channels in this guild.
-        let guild_id = new.guild_id.unwrap();
-        let results = voice_channels::table
-            .filter(voice_channels::guild.eq(TryInto::<i64>::try_into(guild_id.get()).unwrap()))
-            .select(VoiceChannel::as_select())
-            .load(&mut pool.get().unwrap())
-            .unwrap();
-        let guild_channels = guild_id.channels(&cx).await.unwrap();
-        for voice_channel in results {
-            if let Some(channel) =
-                guild_channels.get(&ChannelId::new(voice_channel.id.try_into().unwrap()))
-            {
-                if channel.members(&cx).unwrap().is_empty() {
-                    channel
-                        .delete(&cx)
-                        .await
-                        .expect("Unable to delete empty temporary voice channel.");
-                }
+    }
+}
+
+pub fn owns_voice_channel<C: Into<ChannelId>, U: Into<UserId>>(
+    pool: &ConnectionPool,
+    channel: C,
+    user: U,
+) -> Result<bool, Error> {
+    Ok(voice_channels::table
+        .filter(voice_channels::id.eq(TryInto::<i64>::try_into(channel.into().get()).unwrap()))
+        .filter(voice_channels::creator.eq(TryInto::<i64>::try_into(user.into().get()).unwrap()))
+        .select(voice_channels::id)
+        .first::<i64>(&mut pool.get()?)
+        .optional()?
+        .is_some())
+}
+
+/// The control panel posted in the chat of new channels.
+pub fn control_panel(owner: UserId) -> CreateMessage {
+    let button = |id: &str, label: &str, emoji: &str| {
+        CreateButton::new(format!("{}{}", PANEL_PREFIX, id))
+            .label(label)
+            .emoji(ReactionType::Unicode(emoji.to_string()))
+            .style(ButtonStyle::Secondary)
+    };
+    CreateMessage::new()
+        .content(format!("<@{}>", owner))
+        .embed(
+            CreateEmbed::new()
+                .color(Colour::BLURPLE)
+                .title("You have just created a voice channel!")
+                .description(
+                    "You are the owner of this channel, manage it with the buttons below \
+                    or the `/tempvoice` commands.\n\
+                    \n\
+                    The channel will only get deleted once there's no one here.\n\
+                    If you left the channel while someone is still here, you can come back anytime.\n\
+                    \n\
+                    Note that you are unable to create another new channel unless this channel is deleted.",
+                ),
+        )
+        .components(vec![
+            CreateActionRow::Buttons(vec![
+                button("rename", "Rename", "✏️"),
+                button("limit", "Limit", "👥"),
+                button("lock", "Lock / Unlock", "🔒"),
+            ]),
+            CreateActionRow::Buttons(vec![
+                button("kick", "Kick", "👢"),
+                button("transfer", "Transfer", "👑"),
+                button("delete", "Delete", "🗑️").style(ButtonStyle::Danger),
+            ]),
+        ])
+}
+
+pub async fn rename_impl<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    channel: ChannelId,
+    actor: &User,
+    name: &str,
+) -> Result<String, Error> {
+    let reason = format!("Renamed by @{} ({})", actor.name, actor.id);
+    if channel
+        .edit(cx, EditChannel::new().name(name).audit_log_reason(&reason))
+        .await
+        .is_err()
+    {
+        return Ok("Failed to rename the channel.".to_string());
+    }
+    save_voice_preference(
+        pool,
+        guild,
+        actor.id,
+        VoicePreferenceChange::Name(name.to_string()),
+    )?;
+    Ok(format!("The channel has been renamed to {}", name))
+}
+
+/// Set the user limit of the channel, `0` removes the limit.
+pub async fn limit_impl<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    channel: ChannelId,
+    actor: UserId,
+    count: u32,
+) -> Result<String, Error> {
+    if channel
+        .edit(cx, EditChannel::new().user_limit(count))
+        .await
+        .is_err()
+    {
+        return Ok("Failed to set the user limit.".to_string());
+    }
+    save_voice_preference(pool, guild, actor, VoicePreferenceChange::UserLimit(count))?;
+    Ok(format!(
+        "The user limit of the channel has been set to {}",
+        if count == 0 {
+            "unlimited".to_string()
+        } else {
+            count.to_string()
+        }
+    ))
+}
+
+/// Save a privacy setting of the owner and apply it to their channel, returns `false` if the
+/// permissions couldn't be updated.
+pub async fn update_privacy<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    guild: GuildId,
+    channel: ChannelId,
+    owner: UserId,
+    change: VoicePreferenceChange,
+) -> Result<bool, Error> {
+    save_voice_preference(pool, guild, owner, change)?;
+    if let Err(err) = sync_voice_permissions(cx, pool, guild, channel, owner).await {
+        log::warn!("Failed to update temp voice permissions: {err:?}");
+        return Ok(false);
+    }
+    Ok(true)
+}
+
+/// Give the ownership of the channel to someone in it.
+pub async fn transfer_impl<T: CacheHttp>(
+    cx: &T,
+    pool: &ConnectionPool,
+    channel: &GuildChannel,
+    actor: UserId,
+    new_owner: &User,
+) -> Result<String, Error> {
+    if new_owner.bot || new_owner.id == actor {
+        return Ok("Invalid member".to_string());
+    }
+    let Some(cache) = cx.cache() else {
+        return Ok("Unable to find the members of this channel.".to_string());
+    };
+    if !channel
+        .members(cache)?
+        .iter()
+        .any(|x| x.user.id == new_owner.id)
+    {
+        return Ok("The new owner has to be in this channel.".to_string());
+    }
+    if get_owned_voice_channel(pool, channel.guild_id, new_owner.id)?.is_some() {
+        return Ok("This member already owns a voice channel.".to_string());
+    }
+    set_voice_channel_owner(pool, channel.id, new_owner.id)?;
+    if let Err(err) =
+        sync_voice_permissions(cx, pool, channel.guild_id, channel.id, new_owner.id).await
+    {
+        log::warn!("Failed to update temp voice permissions: {err:?}");
+    }
+    channel
+        .say(
+            cx.http(),
+            format!(
+                "<@{}> transferred the ownership of this channel to <@{}>.",
+                actor, new_owner.id
+            ),
+        )
+        .await?;
+    Ok("The ownership has been transferred.".to_string())
+}
+
+/// The menu to kick members of the channel, `None` if there's no one else.
+pub fn kick_menu(members: &[Member], owner: UserId) -> Option<(CreateEmbed, CreateActionRow)> {
+    let options: Vec<CreateSelectMenuOption> = members
+        .iter()
+        .filter(|x| x.user.id != owner)
+        .map(|x| {
+            CreateSelectMenuOption::new(
+                x.nick.clone().unwrap_or(x.user.display_name().to_string()),
+                x.user.id.get().to_string(),
+            )
+            .description(x.user.name.clone())
+            .emoji(ReactionType::Unicode("👤".to_string()))
+        })
+        .collect();
+    if options.is_empty() {
+        return None;
+    }
+    let select_menu =
+        CreateSelectMenu::new("voice_kick_user", CreateSelectMenuKind::String { options })
+            .placeholder("The user that will be kicked.");
+    Some((
+        CreateEmbed::new()
+            .color(Colour::ORANGE)
+            .title("Kick a User")
+            .description(
+                "To kick a user from your voice channel, select him/her in the following menu.",
+            ),
+        CreateActionRow::SelectMenu(select_menu),
+    ))
+}
+
+fn ephemeral_message<S: Into<String>>(content: S) -> CreateInteractionResponse {
+    CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .ephemeral(true)
+            .content(content),
+    )
+}
+
+async fn handle_panel_button(
+    cx: &Context,
+    pool: &ConnectionPool,
+    interaction: &ComponentInteraction,
+    action: &str,
+) -> Result<(), Error> {
+    let guild_id = interaction.guild_id.unwrap();
+    let channel_id = interaction.channel_id;
+    let owner = interaction.user.id;
+    let response = match action {
+        "rename" => CreateInteractionResponse::Modal(
+            CreateModal::new(format!("{}rename", PANEL_PREFIX), "Rename the Channel").components(
+                vec![CreateActionRow::InputText(
+                    CreateInputText::new(InputTextStyle::Short, "Name", "name").max_length(100),
+                )],
+            ),
+        ),
+        "limit" => CreateInteractionResponse::Modal(
+            CreateModal::new(format!("{}limit", PANEL_PREFIX), "Set the User Limit").components(
+                vec![CreateActionRow::InputText(
+                    CreateInputText::new(InputTextStyle::Short, "Max user count", "count")
+                        .max_length(2)
+                        .required(false)
+                        .placeholder("Leave blank to remove the limit"),
+                )],
+            ),
+        ),
+        "lock" => {
+            let locked = get_voice_preference(pool, guild_id, owner)?.is_some_and(|x| x.locked);
+            let change = VoicePreferenceChange::Locked(!locked);
+            ephemeral_message(
+                match update_privacy(cx, pool, guild_id, channel_id, owner, change).await? {
+                    false => "Failed to update the permissions of the channel.",
+                    true if locked => "The channel has been unlocked.",
+                    true => "The channel has been locked, only permitted members can join.",
+                },
+            )
+        }
+        "kick" => {
+            let channel = channel_id.to_channel(cx).await?.guild().unwrap();
+            match kick_menu(&channel.members(cx)?, owner) {
+                Some((embed, menu)) => CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .embed(embed)
+                        .components(vec![menu]),
+                ),
+                None => ephemeral_message("There's no one else to be kicked."),
+            }
+        }
+        "transfer" => CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .ephemeral(true)
+                .content("Select the new owner of this channel.")
+                .components(vec![CreateActionRow::SelectMenu(
+                    CreateSelectMenu::new(
+                        format!("{}transfer_user", PANEL_PREFIX),
+                        CreateSelectMenuKind::User {
+                            default_users: None,
+                        },
+                    )
+                    .placeholder("The new owner"),
+                )]),
+        ),
+        "transfer_user" => {
+            let ComponentInteractionDataKind::UserSelect { values } = &interaction.data.kind else {
+                return Ok(());
+            };
+            let Some(new_owner) = values.first() else {
+                return Ok(());
+            };
+            let channel = channel_id.to_channel(cx).await?.guild().unwrap();
+            let content =
+                transfer_impl(cx, pool, &channel, owner, &new_owner.to_user(cx).await?).await?;
+            CreateInteractionResponse::UpdateMessage(
+                CreateInteractionResponseMessage::new()
+                    .content(content)
+                    .components(vec![]),
+            )
+        }
+        "delete" => {
+            interaction
+                .create_response(cx, ephemeral_message("The channel is being deleted."))
+                .await?;
+            channel_id.delete(cx).await?;
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+    interaction.create_response(cx, response).await?;
+    Ok(())
+}
+
+async fn handle_panel_modal(
+    cx: &Context,
+    pool: &ConnectionPool,
+    modal: &ModalInteraction,
+    action: &str,
+) -> Result<(), Error> {
+    let guild_id = modal.guild_id.unwrap();
+    let mut value = String::new();
+    for row in &modal.data.components {
+        for comp in &row.components {
+            if let ActionRowComponent::InputText(input) = comp {
+                value = input.value.clone().unwrap_or_default().trim().to_string();
+            }
+        }
+    }
+    let content = match action {
+        "rename" if value.is_empty() => "Invalid name".to_string(),
+        "rename" => rename_impl(cx, pool, guild_id, modal.channel_id, &modal.user, &value).await?,
+        "limit" => match value.parse::<u32>() {
+            Ok(count) if (1..=99).contains(&count) => {
+                limit_impl(cx, pool, guild_id, modal.channel_id, modal.user.id, count).await?
             }
+            _ if value.is_empty() => {
+                limit_impl(cx, pool, guild_id, modal.channel_id, modal.user.id, 0).await?
+            }
+            _ => {
+                "The limit has to be a number between 1 and 99, or blank to remove it.".to_string()
+            }
+        },
+        _ => return Ok(()),
+    };
+    modal
+        .create_response(cx, ephemeral_message(content))
+        .await?;
+    Ok(())
+}
+
+/// Route the control panel components, the ownership is checked on every interaction.
+async fn handle_panel_interaction(cx: &Context, interaction: &Interaction) -> Result<(), Error> {
+    let (id, channel, user) = match interaction {
+        Interaction::Component(x) => (&x.data.custom_id, x.channel_id, x.user.id),
+        Interaction::Modal(x) => (&x.data.custom_id, x.channel_id, x.user.id),
+        _ => return Ok(()),
+    };
+    let Some(action) = id.strip_prefix(PANEL_PREFIX) else {
+        return Ok(());
+    };
+    let pool = get_pool_from_serenity(cx).await;
+    if !owns_voice_channel(&pool, channel, user)? {
+        let response = ephemeral_message("You don't own this channel");
+        match interaction {
+            Interaction::Component(x) => x.create_response(cx, response).await?,
+            Interaction::Modal(x) => x.create_response(cx, response).await?,
+            _ => {}
         }
+        return Ok(());
+    }
+    match interaction {
+        Interaction::Component(x) => handle_panel_button(cx, &pool, x, action).await,
+        Interaction::Modal(x) => handle_panel_modal(cx, &pool, x, action).await,
+        _ => Ok(()),
     }
 }
 
 pub async fn handle_interaction(cx: Context, interaction: Interaction) {
+    if let Err(err) = handle_panel_interaction(&cx, &interaction).await {
+        log::warn!("Failed to handle the temp voice control panel: {err:?}");
+    }
     if let Interaction::Component(interaction) = interaction {
         let id = interaction.data.custom_id.clone();
         if id == *"voice_kick_user" {
@@ -269,3 +1212,71 @@ pub async fn handle_interaction(cx: Context, interaction: Interaction) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_channel_name() {
+        let mut member = Member::default();
+        member.user.name = "someone".to_string();
+        member.nick = Some("Some {n}".to_string());
+        assert_eq!(
+            render_channel_name("{nick}'s room", &member, 2),
+            "Some {n}'s room"
+        );
+        assert_eq!(
+            render_channel_name("Squad #{n} ({username})", &member, 3),
+            "Squad #3 (someone)"
+        );
+        assert_eq!(render_channel_name(&"a".repeat(120), &member, 1).len(), 100);
+    }
+    #[test]
+    fn test_privacy_overwrites() {
+        let guild = GuildId::new(1);
+        let owner = UserId::new(2);
+        let everyone = PermissionOverwriteType::Role(guild.everyone_role());
+        let category = vec![PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES,
+            kind: everyone,
+        }];
+        let mut preference = VoicePreference {
+            guild: 1,
+            member: 2,
+            name: None,
+            user_limit: None,
+            bitrate: None,
+            region: None,
+            updated_at: NaiveDateTime::default(),
+            locked: true,
+            hidden: false,
+            permitted: vec![],
+            rejected: vec![3],
+        };
+        let locked =
+            privacy_overwrites(guild, owner, None, Some(&preference), &category, &category);
+        assert_eq!(
+            locked[0].deny,
+            Permissions::VIEW_CHANNEL | Permissions::SEND_MESSAGES | Permissions::CONNECT
+        );
+        assert_eq!(locked.len(), 3);
+        preference.locked = false;
+        preference.rejected.clear();
+        // unlocking goes back to the overwrites of the category instead of removing them.
+        assert_eq!(
+            privacy_overwrites(guild, owner, None, Some(&preference), &locked, &category),
+            category
+        );
+        // bits that aren't managed here are kept.
+        assert_eq!(
+            privacy_overwrites(guild, owner, None, None, &locked, &[]),
+            vec![PermissionOverwrite {
+                allow: Permissions::empty(),
+                deny: Permissions::SEND_MESSAGES,
+                kind: everyone,
+            }]
+        );
+    }
+}
@@ -0,0 +1,119 @@
+use serenity::all::{CreateAttachment, Message};
+
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+fn escape_html(str: &str) -> String {
+    let mut escaped = String::with_capacity(str.len());
+    for ch in str.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+pub fn render_text(messages: &[Message]) -> String {
+    let mut text = String::new();
+    for msg in messages {
+        text.push_str(&format!(
+            "[{}] @{} ({}): {}\n",
+            msg.timestamp.format(TIME_FORMAT),
+            msg.author.name,
+            msg.author.id,
+            msg.content
+        ));
+        for attachment in &msg.attachments {
+            text.push_str(&format!(
+                "    Attachment: {} - {}\n",
+                attachment.filename, attachment.url
+            ));
+        }
+    }
+    text
+}
+
+pub fn render_html<T: AsRef<str>>(title: T, messages: &[Message]) -> String {
+    let title = escape_html(title.as_ref());
+    let mut html = format!(
+        "<!DOCTYPE html>\n\
+        <html>\n\
+        <head>\n\
+        <meta charset=\"utf-8\">\n\
+        <title>{title}</title>\n\
+        <style>\n\
+        body {{ font-family: sans-serif; background: #313338; color: #dbdee1; }}\n\
+        .message {{ padding: 4px 0; }}\n\
+        .author {{ font-weight: bold; color: #f2f3f5; }}\n\
+        .time, .id {{ font-size: 0.8em; color: #949ba4; }}\n\
+        .content {{ white-space: pre-wrap; }}\n\
+        a {{ color: #00a8fc; }}\n\
+        </style>\n\
+        </head>\n\
+        <body>\n\
+        <h1>{title}</h1>\n"
+    );
+    for msg in messages {
+        html.push_str(&format!(
+            "<div class=\"message\">\n\
+            <span class=\"author\">@{}</span> <span class=\"id\">({})</span> \
+            <span class=\"time\">{}</span>\n\
+            <div class=\"content\">{}</div>\n",
+            escape_html(&msg.author.name),
+            msg.author.id,
+            msg.timestamp.format(TIME_FORMAT),
+            escape_html(&msg.content)
+        ));
+        for attachment in &msg.attachments {
+            html.push_str(&format!(
+                "<div class=\"attachment\"><a href=\"{}\">{}</a></div>\n",
+                escape_html(&attachment.url),
+                escape_html(&attachment.filename)
+            ));
+        }
+        html.push_str("</div>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Create the plain text and HTML transcript files of the messages.
+pub fn create_transcript_attachments<T: AsRef<str>, N: AsRef<str>>(
+    title: T,
+    file_name: N,
+    messages: &[Message],
+) -> [CreateAttachment; 2] {
+    let file_name = file_name.as_ref();
+    [
+        CreateAttachment::bytes(render_text(messages), format!("{}.txt", file_name)),
+        CreateAttachment::bytes(render_html(title, messages), format!("{}.html", file_name)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::all::UserId;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<b>\"Tom\" & 'Jerry'</b>"),
+            "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_render_text() {
+        let mut msg = Message::default();
+        msg.author.id = UserId::new(1);
+        msg.author.name = "someone".to_string();
+        msg.content = "hello".to_string();
+        let text = render_text(&[msg]);
+        assert!(text.ends_with("@someone (1): hello\n"));
+    }
+}
@@ -0,0 +1,188 @@
+/// Diffing bigger inputs would take too much memory, they are shown as fully replaced instead.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+enum DiffOp<'a> {
+    Equal(&'a str),
+    Delete(&'a str),
+    Insert(&'a str),
+}
+
+/// Split the text into words and the whitespace between them.
+pub fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut last_whitespace = None;
+    for (i, ch) in text.char_indices() {
+        let whitespace = ch.is_whitespace();
+        if last_whitespace.is_some_and(|x| x != whitespace) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        last_whitespace = Some(whitespace);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+fn diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
+    if old.len() * new.len() > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(|x| DiffOp::Delete(x))
+            .chain(new.iter().map(|x| DiffOp::Insert(x)))
+            .collect();
+    }
+    // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut ops = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push(DiffOp::Equal(old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffOp::Delete(old[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Insert(new[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[i..].iter().map(|x| DiffOp::Delete(x)));
+    ops.extend(new[j..].iter().map(|x| DiffOp::Insert(x)));
+    ops
+}
+
+fn escape_markdown(str: &str) -> String {
+    let mut escaped = String::with_capacity(str.len());
+    for ch in str.chars() {
+        if matches!(ch, '\\' | '*' | '_' | '~' | '`' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Wrap the text with the markdown marker, keeping the surrounding whitespace outside
+/// since Discord doesn't format markers next to whitespace.
+fn wrap(text: &str, marker: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return String::new();
+    }
+    let start = text.find(trimmed).unwrap();
+    format!(
+        "{}{marker}{}{marker}{}",
+        &text[..start],
+        escape_markdown(trimmed),
+        &text[start + trimmed.len()..]
+    )
+}
+
+fn flush(deleted: &mut String, inserted: &mut String, pieces: &mut Vec<String>) {
+    if deleted.is_empty() && inserted.is_empty() {
+        return;
+    }
+    let mut piece = wrap(deleted, "~~");
+    // deleted whitespace isn't shown, inserted whitespace is kept as is.
+    piece.push_str(&if inserted.trim().is_empty() {
+        inserted.clone()
+    } else {
+        wrap(inserted, "**")
+    });
+    pieces.push(piece);
+    deleted.clear();
+    inserted.clear();
+}
+
+/// Render a word-level diff, deletions are struck through and insertions are bolded.
+///
+/// The result is split into pieces that can be split across messages without breaking the formatting.
+pub fn render_word_diff(old: &str, new: &str) -> Vec<String> {
+    let old = tokenize(old);
+    let new = tokenize(new);
+    let mut pieces = vec![];
+    let mut deleted = String::new();
+    let mut inserted = String::new();
+    // whitespace between two changes belongs to both sides of the change.
+    let mut pending = String::new();
+    for op in diff(&old, &new) {
+        match op {
+            DiffOp::Equal(token) if token.trim().is_empty() => {
+                if deleted.is_empty() && inserted.is_empty() {
+                    pieces.push(token.to_string());
+                } else {
+                    pending.push_str(token);
+                }
+            }
+            DiffOp::Equal(token) => {
+                flush(&mut deleted, &mut inserted, &mut pieces);
+                if !pending.is_empty() {
+                    pieces.push(std::mem::take(&mut pending));
+                }
+                pieces.push(token.to_string());
+            }
+            DiffOp::Delete(token) => {
+                if !pending.is_empty() {
+                    deleted.push_str(&pending);
+                    inserted.push_str(&std::mem::take(&mut pending));
+                }
+                deleted.push_str(token);
+            }
+            DiffOp::Insert(token) => {
+                if !pending.is_empty() {
+                    deleted.push_str(&pending);
+                    inserted.push_str(&std::mem::take(&mut pending));
+                }
+                inserted.push_str(token);
+            }
+        }
+    }
+    flush(&mut deleted, &mut inserted, &mut pieces);
+    if !pending.is_empty() {
+        pieces.push(pending);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("hello  world\n!"),
+            vec!["hello", "  ", "world", "\n", "!"]
+        );
+    }
+
+    #[test]
+    fn test_render_word_diff() {
+        assert_eq!(
+            render_word_diff("the quick brown fox", "the slow brown fox jumps").concat(),
+            "the ~~quick~~**slow** brown fox **jumps**"
+        );
+        assert_eq!(
+            render_word_diff("a b c d", "a x y d").concat(),
+            "a ~~b c~~**x y** d"
+        );
+        assert_eq!(
+            render_word_diff("*hi*", "hi").concat(),
+            "~~\\*hi\\*~~**hi**"
+        );
+    }
+}
@@ -5,7 +5,9 @@ use data::{CacheHttpHolder, ConnectionPoolKey, Data, QueueKey};
 use diesel::{r2d2::ConnectionManager, PgConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use event_handler::Handler;
-use fang::{AsyncQueue, AsyncWorkerPool};
+use fang::{AsyncQueue, AsyncQueueable, AsyncWorkerPool};
+use features::message_store::PruneStoredMessages;
+use features::temp_voice::ReconcileVoiceChannels;
 use lazy_static::lazy_static;
 use logging::{log_framework_error, setup_logger, setup_panic_logger_hook};
 use poise::{FrameworkError, PrefixFrameworkOptions};
@@ -97,6 +99,21 @@ async fn async_main() {
 
     log::info!("Queue created.");
 
+    queue
+        .schedule_task(&PruneStoredMessages::default())
+        .await
+        .expect("Unable to schedule message store pruning.");
+
+    queue
+        .schedule_task(&ReconcileVoiceChannels::default())
+        .await
+        .expect("Unable to schedule temp voice reconciliation.");
+
+    match features::phishing::reload_domains() {
+        Ok(count) => log::info!("Loaded {count} phishing domains."),
+        Err(err) => log::error!("Unable to load phishing domains: {err}"),
+    }
+
     let options = poise::FrameworkOptions::<_, Error> {
         commands: build_commands(),
         prefix_options: PrefixFrameworkOptions {
@@ -142,6 +159,7 @@ async fn async_main() {
         | GatewayIntents::GUILDS
         | GatewayIntents::GUILD_VOICE_STATES
         | GatewayIntents::GUILD_MODERATION
+        | GatewayIntents::AUTO_MODERATION_EXECUTION
         | GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::DIRECT_MESSAGES;
 
@@ -190,11 +208,43 @@ async fn async_main() {
             }
             process::exit(0);
         };
-        let mut sigterm = signal::unix::signal(signal::unix::SignalKind::terminate()).unwrap();
+        #[cfg(unix)]
+        let sigterm = async {
+            match signal::unix::signal(signal::unix::SignalKind::terminate()) {
+                Ok(mut sigterm) => {
+                    sigterm.recv().await;
+                }
+                Err(err) => {
+                    log::error!("Unable to listen for SIGTERM: {err}");
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+        #[cfg(not(unix))]
+        let sigterm = std::future::pending::<()>();
         tokio::select! {
             _ = signal::ctrl_c() => shutdown.await,
-            _ = sigterm.recv() => shutdown.await
+            _ = sigterm => shutdown.await
+        };
+    });
+
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        let mut sighup = match signal::unix::signal(signal::unix::SignalKind::hangup()) {
+            Ok(sighup) => sighup,
+            Err(err) => {
+                log::error!(
+                    "Unable to listen for SIGHUP, phishing domains won't be reloaded: {err}"
+                );
+                return;
+            }
         };
+        while sighup.recv().await.is_some() {
+            match features::phishing::reload_domains() {
+                Ok(count) => log::info!("Reloaded {count} phishing domains."),
+                Err(err) => log::error!("Unable to reload phishing domains: {err}"),
+            }
+        }
     });
 
     if let Err(err) = client.start().await {
@@ -1,4 +1,13 @@
 pub mod allowed_role;
+pub mod filter_rule;
 pub mod guild_settings;
+pub mod lockdown_overwrite;
+pub mod message_log_ignore;
 pub mod moderation_log;
+pub mod phishing_domain;
+pub mod quarantined_member;
+pub mod raid_change;
+pub mod stored_message;
 pub mod voice_channel;
+pub mod voice_creator;
+pub mod voice_preference;
@@ -0,0 +1,190 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    backend::Backend,
+    deserialize::{FromSql, FromSqlRow},
+    expression::AsExpression,
+    prelude::{Insertable, Queryable},
+    serialize::ToSql,
+    sql_types::Text,
+    Selectable,
+};
+use serenity::all::GuildId;
+use uuid::Uuid;
+
+use crate::schema::sql_types::{
+    FilterAction as SqlFilterAction, FilterRuleKind as SqlFilterRuleKind,
+};
+
+#[derive(Debug, Clone, Copy, AsExpression, FromSqlRow, PartialEq, Eq, poise::ChoiceParameter)]
+#[diesel(sql_type = SqlFilterRuleKind)]
+pub enum FilterRuleKind {
+    #[name = "word"]
+    Word,
+    #[name = "glob"]
+    Glob,
+    #[name = "regex"]
+    Regex,
+}
+
+impl TryFrom<String> for FilterRuleKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "word" => Ok(FilterRuleKind::Word),
+            "glob" => Ok(FilterRuleKind::Glob),
+            "regex" => Ok(FilterRuleKind::Regex),
+            x => Err(format!("Unknown variant {}", x)),
+        }
+    }
+}
+
+/// Actions are ordered by severity, the most severe action wins when multiple rules match.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    AsExpression,
+    FromSqlRow,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    poise::ChoiceParameter,
+)]
+#[diesel(sql_type = SqlFilterAction)]
+pub enum FilterAction {
+    #[name = "delete"]
+    Delete,
+    #[name = "warn"]
+    Warn,
+    #[name = "flood"]
+    Flood,
+}
+
+impl FilterAction {
+    pub fn description(&self) -> &str {
+        match self {
+            Self::Delete => "Delete",
+            Self::Warn => "Delete and warn",
+            Self::Flood => "Delete and flood",
+        }
+    }
+}
+
+impl TryFrom<String> for FilterAction {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "delete" => Ok(FilterAction::Delete),
+            "warn" => Ok(FilterAction::Warn),
+            "flood" => Ok(FilterAction::Flood),
+            x => Err(format!("Unknown variant {}", x)),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::filter_rules)]
+pub struct CreateFilterRule {
+    guild: i64,
+    kind: FilterRuleKind,
+    pattern: String,
+    action: FilterAction,
+    duration: Option<String>,
+}
+
+impl CreateFilterRule {
+    pub fn new<G: Into<GuildId>, P: AsRef<str>>(
+        guild: G,
+        kind: FilterRuleKind,
+        pattern: P,
+        action: FilterAction,
+        duration: Option<String>,
+    ) -> Self {
+        CreateFilterRule {
+            guild: guild.into().get().try_into().unwrap(),
+            kind,
+            pattern: pattern.as_ref().to_string(),
+            action,
+            duration,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable, Clone)]
+#[diesel(table_name = crate::schema::filter_rules)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct FilterRule {
+    pub id: Uuid,
+    pub guild: i64,
+    pub kind: FilterRuleKind,
+    pub pattern: String,
+    pub action: FilterAction,
+    pub duration: Option<String>,
+    pub exempt_channels: Vec<i64>,
+    pub exempt_roles: Vec<i64>,
+    pub created_at: NaiveDateTime,
+}
+
+impl<DB> ToSql<SqlFilterRuleKind, DB> for FilterRuleKind
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            FilterRuleKind::Word => "word",
+            FilterRuleKind::Glob => "glob",
+            FilterRuleKind::Regex => "regex",
+        }
+        .to_sql(out)
+    }
+}
+
+impl<DB> FromSql<SqlFilterRuleKind, DB> for FilterRuleKind
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: <DB as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        String::from_sql(bytes)?
+            .try_into()
+            .map_err(|x: String| x.into())
+    }
+}
+
+impl<DB> ToSql<SqlFilterAction, DB> for FilterAction
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            FilterAction::Delete => "delete",
+            FilterAction::Warn => "warn",
+            FilterAction::Flood => "flood",
+        }
+        .to_sql(out)
+    }
+}
+
+impl<DB> FromSql<SqlFilterAction, DB> for FilterAction
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: <DB as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        String::from_sql(bytes)?
+            .try_into()
+            .map_err(|x: String| x.into())
+    }
+}
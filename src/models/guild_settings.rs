@@ -8,7 +8,7 @@ use diesel::{
 use lazy_static::lazy_static;
 use serenity::all::GuildId;
 
-use crate::{schema::guild_settings, ConnectionPool};
+use crate::{schema::guild_settings, ConnectionPool, Error};
 
 mod cache;
 
@@ -80,4 +80,55 @@ impl GuildSettings {
         };
         result.value
     }
+
+    /// Get the value of a setting for every guild that has it set.
+    pub fn all<K: AsRef<str>>(
+        pool: &ConnectionPool,
+        key: K,
+    ) -> Result<Vec<(GuildId, String)>, Error> {
+        let results: Vec<(i64, Option<String>)> = guild_settings::table
+            .filter(guild_settings::key.eq(key.as_ref()))
+            .filter(guild_settings::value.is_not_null())
+            .select((guild_settings::guild, guild_settings::value))
+            .load(&mut pool.get()?)?;
+        Ok(results
+            .into_iter()
+            .filter_map(|(guild, value)| Some((GuildId::new(guild.try_into().unwrap()), value?)))
+            .collect())
+    }
+
+    /// Get a setting that holds a comma separated list of IDs.
+    pub fn get_ids<G: Into<GuildId>, K: AsRef<str>>(
+        pool: &ConnectionPool,
+        guild: G,
+        key: K,
+    ) -> Vec<u64> {
+        Self::get(pool, guild, key)
+            .map(|x| x.split(',').filter_map(|x| x.parse().ok()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Set a setting that holds a comma separated list of IDs, an empty list unsets it.
+    pub fn set_ids<G: Into<GuildId>, K: AsRef<str>>(
+        pool: &ConnectionPool,
+        guild: G,
+        key: K,
+        ids: &[u64],
+    ) -> QueryResult<usize> {
+        Self::set(
+            pool,
+            guild,
+            key,
+            if ids.is_empty() {
+                None
+            } else {
+                Some(
+                    ids.iter()
+                        .map(|x| x.to_string())
+                        .collect::<Vec<String>>()
+                        .join(","),
+                )
+            },
+        )
+    }
 }
@@ -0,0 +1,69 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    prelude::{Insertable, Queryable},
+    Selectable,
+};
+use serenity::all::{
+    ChannelId, GuildId, PermissionOverwrite, PermissionOverwriteType, Permissions, RoleId,
+};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::lockdown_overwrites)]
+pub struct CreateLockdownOverwrite {
+    guild: i64,
+    channel: i64,
+    role: i64,
+    allow: Option<i64>,
+    deny: Option<i64>,
+    reason: Option<String>,
+}
+
+impl CreateLockdownOverwrite {
+    /// Snapshot the overwrite of the role before locking, `None` if the role had no overwrite.
+    pub fn new<G: Into<GuildId>, C: Into<ChannelId>>(
+        guild: G,
+        channel: C,
+        role: u64,
+        previous: Option<&PermissionOverwrite>,
+        reason: Option<String>,
+    ) -> Self {
+        CreateLockdownOverwrite {
+            guild: guild.into().get().try_into().unwrap(),
+            channel: channel.into().get().try_into().unwrap(),
+            role: role.try_into().unwrap(),
+            allow: previous.map(|x| x.allow.bits() as i64),
+            deny: previous.map(|x| x.deny.bits() as i64),
+            reason,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::lockdown_overwrites)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct LockdownOverwrite {
+    pub guild: i64,
+    pub channel: i64,
+    pub role: i64,
+    pub allow: Option<i64>,
+    pub deny: Option<i64>,
+    pub reason: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl LockdownOverwrite {
+    /// The overwrite to restore, `None` if the overwrite should be removed.
+    pub fn previous(&self) -> Option<PermissionOverwrite> {
+        let (allow, deny) = (self.allow?, self.deny?);
+        Some(PermissionOverwrite {
+            allow: Permissions::from_bits_truncate(allow as u64),
+            deny: Permissions::from_bits_truncate(deny as u64),
+            kind: PermissionOverwriteType::Role(self.role_id()),
+        })
+    }
+
+    pub fn role_id(&self) -> RoleId {
+        RoleId::new(self.role.try_into().unwrap())
+    }
+}
@@ -0,0 +1,127 @@
+use diesel::{
+    backend::Backend,
+    deserialize::{FromSql, FromSqlRow},
+    expression::AsExpression,
+    prelude::{Insertable, Queryable},
+    serialize::ToSql,
+    sql_types::Text,
+    Selectable,
+};
+use serenity::all::GuildId;
+
+use crate::schema::sql_types::MessageLogIgnoreKind as SqlMessageLogIgnoreKind;
+
+#[derive(Debug, Clone, Copy, AsExpression, FromSqlRow, PartialEq, Eq, Hash)]
+#[diesel(sql_type = SqlMessageLogIgnoreKind)]
+pub enum MessageLogIgnoreKind {
+    Channel,
+    Category,
+    Role,
+    User,
+    Bots,
+    Webhooks,
+}
+
+impl MessageLogIgnoreKind {
+    /// Format the ignored target the way it is shown in Discord.
+    pub fn mention(&self, target: i64) -> String {
+        match self {
+            Self::Channel | Self::Category => format!("<#{}>", target),
+            Self::Role => format!("<@&{}>", target),
+            Self::User => format!("<@{}>", target),
+            Self::Bots => "All bots".to_string(),
+            Self::Webhooks => "All webhooks".to_string(),
+        }
+    }
+}
+
+impl TryFrom<String> for MessageLogIgnoreKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "channel" => Ok(MessageLogIgnoreKind::Channel),
+            "category" => Ok(MessageLogIgnoreKind::Category),
+            "role" => Ok(MessageLogIgnoreKind::Role),
+            "user" => Ok(MessageLogIgnoreKind::User),
+            "bots" => Ok(MessageLogIgnoreKind::Bots),
+            "webhooks" => Ok(MessageLogIgnoreKind::Webhooks),
+            x => Err(format!("Unknown variant {}", x)),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::message_log_ignores)]
+pub struct CreateMessageLogIgnore {
+    guild: i64,
+    kind: MessageLogIgnoreKind,
+    target: i64,
+}
+
+impl CreateMessageLogIgnore {
+    /// `target` is ignored for bots and webhooks since they apply to the whole server.
+    pub fn new<G: Into<GuildId>>(
+        guild: G,
+        kind: MessageLogIgnoreKind,
+        target: Option<u64>,
+    ) -> Self {
+        CreateMessageLogIgnore {
+            guild: guild.into().get().try_into().unwrap(),
+            kind,
+            target: match kind {
+                MessageLogIgnoreKind::Bots | MessageLogIgnoreKind::Webhooks => 0,
+                _ => target.unwrap_or_default().try_into().unwrap(),
+            },
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::message_log_ignores)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct MessageLogIgnore {
+    pub guild: i64,
+    pub kind: MessageLogIgnoreKind,
+    pub target: i64,
+}
+
+impl MessageLogIgnore {
+    pub fn matches(&self, kind: MessageLogIgnoreKind, target: u64) -> bool {
+        self.kind == kind && TryInto::<u64>::try_into(self.target).unwrap() == target
+    }
+}
+
+impl<DB> ToSql<SqlMessageLogIgnoreKind, DB> for MessageLogIgnoreKind
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            MessageLogIgnoreKind::Channel => "channel",
+            MessageLogIgnoreKind::Category => "category",
+            MessageLogIgnoreKind::Role => "role",
+            MessageLogIgnoreKind::User => "user",
+            MessageLogIgnoreKind::Bots => "bots",
+            MessageLogIgnoreKind::Webhooks => "webhooks",
+        }
+        .to_sql(out)
+    }
+}
+
+impl<DB> FromSql<SqlMessageLogIgnoreKind, DB> for MessageLogIgnoreKind
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: <DB as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        String::from_sql(bytes)?
+            .try_into()
+            .map_err(|x: String| x.into())
+    }
+}
@@ -10,7 +10,7 @@ use diesel::{
     query_builder::IncompleteInsertStatement,
     serialize::ToSql,
     sql_types::Text,
-    ExpressionMethods, QueryDsl, Selectable, SelectableHelper,
+    BoolExpressionMethods, ExpressionMethods, QueryDsl, Selectable, SelectableHelper,
 };
 use serenity::all::{Colour, CreateEmbed, GuildId, UserId};
 use uuid::Uuid;
@@ -20,6 +20,8 @@ use crate::schema::{moderation_log, sql_types::ModerationAction as SqlModeration
 #[derive(Debug, Clone, AsExpression, FromSqlRow, PartialEq, Eq, Hash)]
 #[diesel(sql_type = SqlModerationAction)]
 pub enum ModerationAction {
+    /// A message blocked by Discord AutoMod.
+    Block,
     Warning,
     Flood,
     Timeout,
@@ -30,6 +32,7 @@ pub enum ModerationAction {
 impl ModerationAction {
     pub fn embed_title(&self) -> &str {
         match self {
+            Self::Block => "⛔ Block",
             Self::Warning => "🔔 Warning",
             Self::Flood => "🔒 Flood",
             Self::Timeout => "🔇 Timeout",
@@ -40,6 +43,7 @@ impl ModerationAction {
 
     pub fn embed_color(&self) -> Colour {
         match self {
+            Self::Block => Colour::GOLD,
             Self::Warning => Colour::ORANGE,
             Self::Flood => Colour::LIGHT_GREY,
             Self::Timeout => Colour::PURPLE,
@@ -60,6 +64,7 @@ impl TryFrom<String> for ModerationAction {
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
         match value.as_str() {
+            "block" => Ok(ModerationAction::Block),
             "warning" => Ok(ModerationAction::Warning),
             "flood" => Ok(ModerationAction::Flood),
             "timeout" => Ok(ModerationAction::Timeout),
@@ -78,6 +83,7 @@ pub struct CreateModerationLog {
     member: i64,
     actor: Option<i64>,
     reason: Option<String>,
+    expires_at: Option<NaiveDateTime>,
 }
 
 impl CreateModerationLog {
@@ -94,8 +100,14 @@ impl CreateModerationLog {
             member: member.into().get().try_into().unwrap(),
             actor: actor.map(|x| x.into().get().try_into().unwrap()),
             reason: reason.map(|x| x.as_ref().to_string()),
+            expires_at: None,
         }
     }
+
+    pub fn expires_at(mut self, expires_at: Option<NaiveDateTime>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
 }
 
 #[allow(dead_code)]
@@ -110,6 +122,7 @@ pub struct ModerationLog {
     pub reason: Option<String>,
     pub created_at: NaiveDateTime,
     pub updated_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
 }
 
 impl ModerationLog {
@@ -145,6 +158,19 @@ impl ModerationLog {
     pub fn no_actor() -> _ {
         moderation_log::actor.is_null()
     }
+
+    #[diesel::dsl::auto_type(no_type_alias)]
+    pub fn active() -> _ {
+        moderation_log::expires_at
+            .is_null()
+            .or(moderation_log::expires_at.gt(diesel::dsl::now))
+    }
+
+    /// A case is active until it expires, cases without an expiry are always active.
+    pub fn is_active(&self) -> bool {
+        self.expires_at
+            .is_none_or(|x| x > chrono::Utc::now().naive_utc())
+    }
 }
 
 impl<DB> ToSql<SqlModerationAction, DB> for ModerationAction
@@ -157,6 +183,7 @@ where
         out: &mut diesel::serialize::Output<'b, '_, DB>,
     ) -> diesel::serialize::Result {
         match self {
+            ModerationAction::Block => "block",
             ModerationAction::Warning => "warning",
             ModerationAction::Flood => "flood",
             ModerationAction::Timeout => "timeout",
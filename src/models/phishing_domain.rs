@@ -0,0 +1,32 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    prelude::{Insertable, Queryable},
+    Selectable,
+};
+use serenity::all::GuildId;
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::phishing_domains)]
+pub struct CreatePhishingDomain {
+    guild: i64,
+    domain: String,
+}
+
+impl CreatePhishingDomain {
+    pub fn new<G: Into<GuildId>, D: AsRef<str>>(guild: G, domain: D) -> Self {
+        CreatePhishingDomain {
+            guild: guild.into().get().try_into().unwrap(),
+            domain: domain.as_ref().to_string(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::phishing_domains)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PhishingDomain {
+    pub guild: i64,
+    pub domain: String,
+    pub created_at: NaiveDateTime,
+}
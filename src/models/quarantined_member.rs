@@ -0,0 +1,46 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    prelude::{Insertable, Queryable},
+    Selectable,
+};
+use serenity::all::{GuildId, RoleId, UserId};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::quarantined_members)]
+pub struct CreateQuarantinedMember {
+    guild: i64,
+    member: i64,
+    role: i64,
+}
+
+impl CreateQuarantinedMember {
+    pub fn new<G: Into<GuildId>, U: Into<UserId>, R: Into<RoleId>>(
+        guild: G,
+        member: U,
+        role: R,
+    ) -> Self {
+        CreateQuarantinedMember {
+            guild: guild.into().get().try_into().unwrap(),
+            member: member.into().get().try_into().unwrap(),
+            role: role.into().get().try_into().unwrap(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::quarantined_members)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct QuarantinedMember {
+    pub guild: i64,
+    pub member: i64,
+    /// The quarantine role at the time the member was quarantined.
+    pub role: i64,
+    pub created_at: NaiveDateTime,
+}
+
+impl QuarantinedMember {
+    pub fn role_id(&self) -> RoleId {
+        RoleId::new(self.role.try_into().unwrap())
+    }
+}
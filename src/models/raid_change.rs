@@ -0,0 +1,107 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    backend::Backend,
+    deserialize::{FromSql, FromSqlRow},
+    expression::AsExpression,
+    prelude::{Insertable, Queryable},
+    serialize::ToSql,
+    sql_types::Text,
+    Selectable,
+};
+use serenity::all::{GuildId, RoleId};
+use uuid::Uuid;
+
+use crate::schema::sql_types::RaidChangeKind as SqlRaidChangeKind;
+
+#[derive(Debug, Clone, Copy, AsExpression, FromSqlRow, PartialEq, Eq)]
+#[diesel(sql_type = SqlRaidChangeKind)]
+pub enum RaidChangeKind {
+    /// Slowmode applied to the target channel, the previous rate limit is kept to restore it.
+    Slowmode,
+    /// Quarantine role given to the target user.
+    Quarantine,
+}
+
+impl TryFrom<String> for RaidChangeKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "slowmode" => Ok(RaidChangeKind::Slowmode),
+            "quarantine" => Ok(RaidChangeKind::Quarantine),
+            x => Err(format!("Unknown variant {}", x)),
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::raid_changes)]
+pub struct CreateRaidChange {
+    guild: i64,
+    kind: RaidChangeKind,
+    target: i64,
+    previous_value: Option<i32>,
+    role: Option<i64>,
+}
+
+impl CreateRaidChange {
+    pub fn new<G: Into<GuildId>>(
+        guild: G,
+        kind: RaidChangeKind,
+        target: u64,
+        previous_value: Option<i32>,
+        role: Option<RoleId>,
+    ) -> Self {
+        CreateRaidChange {
+            guild: guild.into().get().try_into().unwrap(),
+            kind,
+            target: target.try_into().unwrap(),
+            previous_value,
+            role: role.map(|x| x.get().try_into().unwrap()),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::raid_changes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct RaidChange {
+    pub id: Uuid,
+    pub guild: i64,
+    pub kind: RaidChangeKind,
+    pub target: i64,
+    pub previous_value: Option<i32>,
+    pub created_at: NaiveDateTime,
+    /// The quarantine role given to the target user.
+    pub role: Option<i64>,
+}
+
+impl<DB> ToSql<SqlRaidChangeKind, DB> for RaidChangeKind
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            RaidChangeKind::Slowmode => "slowmode",
+            RaidChangeKind::Quarantine => "quarantine",
+        }
+        .to_sql(out)
+    }
+}
+
+impl<DB> FromSql<SqlRaidChangeKind, DB> for RaidChangeKind
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: <DB as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        String::from_sql(bytes)?
+            .try_into()
+            .map_err(|x: String| x.into())
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    prelude::{Insertable, Queryable},
+    Selectable,
+};
+use serenity::all::{Attachment, ChannelId, GuildId, Message, MessageId, User};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::stored_messages)]
+pub struct CreateStoredMessage {
+    id: i64,
+    guild: i64,
+    channel: i64,
+    author: i64,
+    content: String,
+    attachments: serde_json::Value,
+}
+
+impl CreateStoredMessage {
+    pub fn new<G: Into<GuildId>>(guild: G, message: &Message) -> Self {
+        CreateStoredMessage {
+            id: message.id.get().try_into().unwrap(),
+            guild: guild.into().get().try_into().unwrap(),
+            channel: message.channel_id.get().try_into().unwrap(),
+            author: message.author.id.get().try_into().unwrap(),
+            content: message.content.clone(),
+            attachments: serde_json::to_value(&message.attachments).unwrap(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::stored_messages)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct StoredMessage {
+    pub id: i64,
+    pub guild: i64,
+    pub channel: i64,
+    pub author: i64,
+    pub content: String,
+    pub attachments: serde_json::Value,
+    pub created_at: NaiveDateTime,
+    pub edited_at: Option<NaiveDateTime>,
+}
+
+impl StoredMessage {
+    /// Rebuilds a message from the stored record, only the fields we store are filled.
+    pub fn into_message(self, author: User) -> Message {
+        let mut message = Message::default();
+        message.id = MessageId::new(self.id.try_into().unwrap());
+        message.guild_id = Some(GuildId::new(self.guild.try_into().unwrap()));
+        message.channel_id = ChannelId::new(self.channel.try_into().unwrap());
+        message.timestamp = message.id.created_at();
+        message.author = author;
+        message.content = self.content;
+        message.attachments =
+            serde_json::from_value::<Vec<Attachment>>(self.attachments).unwrap_or_default();
+        message
+    }
+}
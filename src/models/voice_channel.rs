@@ -11,18 +11,24 @@ pub struct CreateVoiceChannel {
     id: i64,
     guild: i64,
     creator: i64,
+    creator_channel: i64,
+    number: i32,
 }
 
 impl CreateVoiceChannel {
-    pub fn new<T: Into<ChannelId>, G: Into<GuildId>, U: Into<UserId>>(
+    pub fn new<T: Into<ChannelId>, G: Into<GuildId>, U: Into<UserId>, C: Into<ChannelId>>(
         channel_id: T,
         guild: G,
         creator: U,
+        creator_channel: C,
+        number: i32,
     ) -> Self {
         CreateVoiceChannel {
             id: channel_id.into().get().try_into().unwrap(),
             guild: guild.into().get().try_into().unwrap(),
             creator: creator.into().get().try_into().unwrap(),
+            creator_channel: creator_channel.into().get().try_into().unwrap(),
+            number,
         }
     }
 }
@@ -36,4 +42,9 @@ pub struct VoiceChannel {
     pub guild: i64,
     pub creator: i64,
     pub created_at: NaiveDateTime,
+    /// `None` for channels created before creator channels had templates.
+    pub creator_channel: Option<i64>,
+    pub number: Option<i32>,
+    /// When the owner left the channel, `None` while they are in it.
+    pub owner_left_at: Option<NaiveDateTime>,
 }
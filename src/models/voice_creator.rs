@@ -0,0 +1,135 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    backend::Backend,
+    deserialize::{FromSql, FromSqlRow},
+    expression::AsExpression,
+    prelude::{AsChangeset, Insertable, Queryable},
+    serialize::ToSql,
+    sql_types::Text,
+    Selectable,
+};
+use serenity::all::{ChannelId, ChannelType, GuildId};
+
+use crate::schema::sql_types::VoiceCreatorKind as SqlVoiceCreatorKind;
+
+#[derive(Debug, Clone, Copy, AsExpression, FromSqlRow, PartialEq, Eq, poise::ChoiceParameter)]
+#[diesel(sql_type = SqlVoiceCreatorKind)]
+pub enum VoiceCreatorKind {
+    #[name = "voice"]
+    Voice,
+    #[name = "stage"]
+    Stage,
+}
+
+impl VoiceCreatorKind {
+    pub fn channel_type(&self) -> ChannelType {
+        match self {
+            VoiceCreatorKind::Voice => ChannelType::Voice,
+            VoiceCreatorKind::Stage => ChannelType::Stage,
+        }
+    }
+}
+
+impl TryFrom<String> for VoiceCreatorKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "voice" => Ok(VoiceCreatorKind::Voice),
+            "stage" => Ok(VoiceCreatorKind::Stage),
+            x => Err(format!("Unknown variant {}", x)),
+        }
+    }
+}
+
+/// Template of the channels created by joining a creator channel.
+#[derive(Insertable, AsChangeset)]
+#[diesel(table_name = crate::schema::voice_creators)]
+#[diesel(treat_none_as_null = true)]
+pub struct CreateVoiceCreator {
+    channel: i64,
+    guild: i64,
+    name_pattern: String,
+    category: Option<i64>,
+    user_limit: Option<i32>,
+    bitrate: Option<i32>,
+    kind: VoiceCreatorKind,
+}
+
+impl CreateVoiceCreator {
+    pub fn new<C: Into<ChannelId>, G: Into<GuildId>, S: AsRef<str>>(
+        channel: C,
+        guild: G,
+        name_pattern: S,
+        category: Option<ChannelId>,
+        user_limit: Option<u32>,
+        bitrate: Option<u32>,
+        kind: VoiceCreatorKind,
+    ) -> Self {
+        CreateVoiceCreator {
+            channel: channel.into().get().try_into().unwrap(),
+            guild: guild.into().get().try_into().unwrap(),
+            name_pattern: name_pattern.as_ref().to_string(),
+            category: category.map(|x| x.get().try_into().unwrap()),
+            user_limit: user_limit.map(|x| x.try_into().unwrap()),
+            bitrate: bitrate.map(|x| x.try_into().unwrap()),
+            kind,
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::voice_creators)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct VoiceCreator {
+    pub channel: i64,
+    pub guild: i64,
+    /// Supports `{nick}`, `{username}` and `{n}`, the lowest free number of this creator.
+    pub name_pattern: String,
+    pub category: Option<i64>,
+    pub user_limit: Option<i32>,
+    /// In bits per second.
+    pub bitrate: Option<i32>,
+    pub kind: VoiceCreatorKind,
+    pub created_at: NaiveDateTime,
+}
+
+impl VoiceCreator {
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel.try_into().unwrap())
+    }
+
+    pub fn category_id(&self) -> Option<ChannelId> {
+        self.category.map(|x| ChannelId::new(x.try_into().unwrap()))
+    }
+}
+
+impl<DB> ToSql<SqlVoiceCreatorKind, DB> for VoiceCreatorKind
+where
+    DB: Backend,
+    str: ToSql<Text, DB>,
+{
+    fn to_sql<'b>(
+        &'b self,
+        out: &mut diesel::serialize::Output<'b, '_, DB>,
+    ) -> diesel::serialize::Result {
+        match self {
+            VoiceCreatorKind::Voice => "voice",
+            VoiceCreatorKind::Stage => "stage",
+        }
+        .to_sql(out)
+    }
+}
+
+impl<DB> FromSql<SqlVoiceCreatorKind, DB> for VoiceCreatorKind
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: <DB as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        String::from_sql(bytes)?
+            .try_into()
+            .map_err(|x: String| x.into())
+    }
+}
@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use diesel::{
+    prelude::{Insertable, Queryable},
+    Selectable,
+};
+use serenity::all::{GuildId, UserId};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::voice_preferences)]
+pub struct CreateVoicePreference {
+    guild: i64,
+    member: i64,
+}
+
+impl CreateVoicePreference {
+    pub fn new<G: Into<GuildId>, U: Into<UserId>>(guild: G, member: U) -> Self {
+        CreateVoicePreference {
+            guild: guild.into().get().try_into().unwrap(),
+            member: member.into().get().try_into().unwrap(),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Queryable, Selectable)]
+#[diesel(table_name = crate::schema::voice_preferences)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct VoicePreference {
+    pub guild: i64,
+    pub member: i64,
+    pub name: Option<String>,
+    /// `0` means unlimited.
+    pub user_limit: Option<i32>,
+    /// In bits per second.
+    pub bitrate: Option<i32>,
+    pub region: Option<String>,
+    pub updated_at: NaiveDateTime,
+    /// Only permitted members can join.
+    pub locked: bool,
+    /// Only permitted members can see the channel.
+    pub hidden: bool,
+    pub permitted: Vec<i64>,
+    /// Members that can neither see nor join the channel.
+    pub rejected: Vec<i64>,
+}
@@ -5,9 +5,29 @@ pub mod sql_types {
     #[diesel(postgres_type(name = "fang_task_state"))]
     pub struct FangTaskState;
 
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "filter_action"))]
+    pub struct FilterAction;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "filter_rule_kind"))]
+    pub struct FilterRuleKind;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "message_log_ignore_kind"))]
+    pub struct MessageLogIgnoreKind;
+
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "moderation_action"))]
     pub struct ModerationAction;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "raid_change_kind"))]
+    pub struct RaidChangeKind;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "voice_creator_kind"))]
+    pub struct VoiceCreatorKind;
 }
 
 diesel::table! {
@@ -37,6 +57,24 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::FilterRuleKind;
+    use super::sql_types::FilterAction;
+
+    filter_rules (id) {
+        id -> Uuid,
+        guild -> Int8,
+        kind -> FilterRuleKind,
+        pattern -> Text,
+        action -> FilterAction,
+        duration -> Nullable<Text>,
+        exempt_channels -> Array<Int8>,
+        exempt_roles -> Array<Int8>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     guild_settings (guild, key) {
         guild -> Int8,
@@ -45,6 +83,29 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    lockdown_overwrites (channel, role) {
+        guild -> Int8,
+        channel -> Int8,
+        role -> Int8,
+        allow -> Nullable<Int8>,
+        deny -> Nullable<Int8>,
+        reason -> Nullable<Text>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::MessageLogIgnoreKind;
+
+    message_log_ignores (guild, kind, target) {
+        guild -> Int8,
+        kind -> MessageLogIgnoreKind,
+        target -> Int8,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::ModerationAction;
@@ -58,6 +119,7 @@ diesel::table! {
         reason -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Nullable<Timestamp>,
+        expires_at -> Nullable<Timestamp>,
     }
 }
 
@@ -70,12 +132,92 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    phishing_domains (guild, domain) {
+        guild -> Int8,
+        domain -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    quarantined_members (guild, member) {
+        guild -> Int8,
+        member -> Int8,
+        role -> Int8,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::RaidChangeKind;
+
+    raid_changes (id) {
+        id -> Uuid,
+        guild -> Int8,
+        kind -> RaidChangeKind,
+        target -> Int8,
+        previous_value -> Nullable<Int4>,
+        created_at -> Timestamp,
+        role -> Nullable<Int8>,
+    }
+}
+
+diesel::table! {
+    stored_messages (id) {
+        id -> Int8,
+        guild -> Int8,
+        channel -> Int8,
+        author -> Int8,
+        content -> Text,
+        attachments -> Jsonb,
+        created_at -> Timestamp,
+        edited_at -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     voice_channels (id) {
         id -> Int8,
         guild -> Int8,
         creator -> Int8,
         created_at -> Timestamp,
+        creator_channel -> Nullable<Int8>,
+        number -> Nullable<Int4>,
+        owner_left_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::VoiceCreatorKind;
+
+    voice_creators (channel) {
+        channel -> Int8,
+        guild -> Int8,
+        name_pattern -> Text,
+        category -> Nullable<Int8>,
+        user_limit -> Nullable<Int4>,
+        bitrate -> Nullable<Int4>,
+        kind -> VoiceCreatorKind,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    voice_preferences (guild, member) {
+        guild -> Int8,
+        member -> Int8,
+        name -> Nullable<Text>,
+        user_limit -> Nullable<Int4>,
+        bitrate -> Nullable<Int4>,
+        region -> Nullable<Text>,
+        updated_at -> Timestamp,
+        locked -> Bool,
+        hidden -> Bool,
+        permitted -> Array<Int8>,
+        rejected -> Array<Int8>,
     }
 }
 
@@ -84,8 +226,17 @@ diesel::joinable!(moderation_log_message -> moderation_log (log_id));
 diesel::allow_tables_to_appear_in_same_query!(
     allowed_roles,
     fang_tasks,
+    filter_rules,
     guild_settings,
+    lockdown_overwrites,
+    message_log_ignores,
     moderation_log,
     moderation_log_message,
+    phishing_domains,
+    quarantined_members,
+    raid_changes,
+    stored_messages,
     voice_channels,
+    voice_creators,
+    voice_preferences,
 );
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use chrono::NaiveDateTime;
 use diesel::{ExpressionMethods, RunQueryDsl};
 use serenity::all::{CacheHttp, ChannelId, Context, CreateMessage, GuildId, Message};
 
@@ -50,6 +51,10 @@ pub fn parse_duration_to_seconds<T: AsRef<str>>(duration: T) -> Result<u64, Stri
     Ok(total_seconds)
 }
 
+pub fn expiry_from_now(seconds: u64) -> NaiveDateTime {
+    (chrono::Utc::now() + std::time::Duration::from_secs(seconds)).naive_utc()
+}
+
 pub async fn get_pool_from_serenity(cx: &Context) -> ConnectionPool {
     cx.data
         .read()